@@ -8,12 +8,13 @@
 //! Points can be organised into named groups in order restrict processing to a subset of points.
 //! Both, shapes and groups, relate the underlying point via handles.
 
+use crate::graph::{MeasureOutput, OutputSize, ParameterType};
+use crate::hardeen_error::HardeenError;
 use crate::handled_vec::*;
 use serde::Serialize;
 use std::vec::*;
 
 use std::fmt;
-use std::num::ParseFloatError;
 use std::ops::{Add, Mul, Sub, AddAssign, Div};
 use std::str::FromStr;
 
@@ -42,12 +43,24 @@ impl Position {
 }
 
 impl FromStr for Position {
-    type Err = ParseFloatError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    type Err = HardeenError;
+    /// Parses "x,y". Surrounding parentheses are accepted as well.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_value = || HardeenError::ParameterValueInvalid {
+            parameter_type: ParameterType::Position,
+            value: String::from(value)
+        };
+
+        let s = value.trim();
+        let s = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')).unwrap_or(s);
         let coords: Vec<&str> = s.split(',').collect();
 
-        let x = coords[0].parse::<f32>()?;
-        let y = coords[1].parse::<f32>()?;
+        if coords.len() != 2 {
+            return Err(invalid_value());
+        }
+
+        let x = coords[0].trim().parse::<f32>().map_err(|_| invalid_value())?;
+        let y = coords[1].trim().parse::<f32>().map_err(|_| invalid_value())?;
 
         Ok(Position(x, y))
     }
@@ -214,6 +227,9 @@ pub type ShapeDataVector = ImmutableVector<Shape>;
 pub type ShapeHandleIterator<'a> = HandleIterator<'a, ShapeHandle, ShapeDataVector>;
 pub type ShapeIterator<'a> = DataIterator<'a, ShapeDataVector>;
 
+/// Name of the group every point of a `GeometryWorld` belongs to.
+pub const ALL_POINTS_GROUP_NAME: &str = "all";
+
 #[derive(Debug)]
 pub enum GeometryWorldError {
     Error(&'static str),
@@ -236,7 +252,7 @@ impl GeometryWorld {
             all_point_group_handle: GroupHandle::new(0, 0),
         };
 
-        world.all_point_group_handle = world.create_group("all");
+        world.all_point_group_handle = world.create_group(ALL_POINTS_GROUP_NAME);
 
         world
     }
@@ -491,3 +507,19 @@ impl GeometryWorld {
         (nw.clone(), se.clone())
     }
 }
+
+impl Default for GeometryWorld {
+    fn default() -> Self {
+        GeometryWorld::new()
+    }
+}
+
+impl MeasureOutput for GeometryWorld {
+    fn measure_output(&self) -> OutputSize {
+        OutputSize {
+            points: self.points.get_entity_count(),
+            shapes: self.shapes.get_entity_count(),
+            groups: self.groups.get_entity_count(),
+        }
+    }
+}
@@ -7,101 +7,72 @@ use crate::geometry::*;
 
 use crate::handled_vec::Handle;
 use crate::hardeen_error::HardeenError;
+use crate::shared::*;
 
-use std::rc::Rc;
 use std::vec::Vec;
 use rand::prelude::*;
 use std::collections::HashMap;
 use std::cmp::Ordering;
 use std::iter;
+use std::sync::OnceLock;
 use itertools::Itertools;
 
-impl Graph<GeometryWorld> {
+impl ProcessorRegistry<GeometryWorld> {
+
+    /// Creates a registry that contains all processors Hardeen provides for `GeometryWorld`s.
+    pub fn with_builtin_processors() -> Self {
+        let mut registry = ProcessorRegistry::new();
+
+        registry.register_basic_processor(Empty::get_processor_type_info(), || Box::new(Empty::new()));
+        registry.register_basic_processor(CreateRectangle::get_processor_type_info(), || Box::new(CreateRectangle::new()));
+        registry.register_basic_processor(ScatterPoints::get_processor_type_info(), || Box::new(ScatterPoints::new()));
+        registry.register_basic_processor(Scale::get_processor_type_info(), || Box::new(Scale::new()));
+        registry.register_basic_processor(RandomTangents::get_processor_type_info(), || Box::new(RandomTangents::new()));
+        registry.register_basic_processor(SmoothTangents::get_processor_type_info(), || Box::new(SmoothTangents::new()));
+        registry.register_basic_processor(AddPoints::get_processor_type_info(), || Box::new(AddPoints::new()));
+        registry.register_basic_processor(Merge::get_processor_type_info(), || Box::new(Merge::new()));
+        registry.register_basic_processor(CopyPointsAndOffset::get_processor_type_info(), || Box::new(CopyPointsAndOffset::new()));
+        registry.register_basic_processor(SortPointsX::get_processor_type_info(), || Box::new(SortPointsX::new()));
+        registry.register_basic_processor(CreateShapeFromGroup::get_processor_type_info(), || Box::new(CreateShapeFromGroup::new()));
+        registry.register_basic_processor(CreateShapeFromAllGroups::get_processor_type_info(), || Box::new(CreateShapeFromAllGroups::new()));
+        registry.register_basic_processor(Translate::get_processor_type_info(), || Box::new(Translate::new()));
+        registry.register_basic_processor(TranslateOverTime::get_processor_type_info(), || Box::new(TranslateOverTime::new()));
+        registry.register_basic_processor(RandomTranslate::get_processor_type_info(), || Box::new(RandomTranslate::new()));
+        registry.register_basic_processor(CopyPointsAndRandomOffset::get_processor_type_info(), || Box::new(CopyPointsAndRandomOffset::new()));
+        registry.register_basic_processor(ExtrudeShape::get_processor_type_info(), || Box::new(ExtrudeShape::new()));
+        registry.register_basic_processor(GroupPoints::get_processor_type_info(), || Box::new(GroupPoints::new()));
+        registry.register_subgraph_processor(InstanceOnPoints::get_processor_type_info(), || Box::new(InstanceOnPoints::new()));
+        registry.register_subgraph_processor(Subnet::get_processor_type_info(), || Box::new(Subnet::new()));
+        registry.register_basic_processor(GraphInput::get_processor_type_info(), || Box::new(GraphInput::new()));
+
+        registry
+    }
+
+}
 
-    pub fn add_processor_node_by_type(&mut self, processor_type: &str) -> NodeHandle<GeometryWorld> {
-        match processor_type {
-            "Empty" => {
-                let node = Empty::new();
-                self.add_processor_node(Box::from(node))
+impl std::convert::From<GeometryWorldError> for HardeenError {
+    fn from(error: GeometryWorldError) -> HardeenError {
+        match error {
+            GeometryWorldError::Error(message) => HardeenError::ProcessingFailed {
+                message: String::from(message)
             }
-            "CreateRectangle" => {
-                let node = CreateRectangle::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "ScatterPoints" => {
-                let node = ScatterPoints::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "Scale" => {
-                let node = Scale::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "RandomTangents" => {
-                let node = RandomTangents::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "SmoothTangents" => {
-                let node = SmoothTangents::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "AddPoints" => {
-                let node = AddPoints::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "Merge" => {
-                let node = Merge::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "CopyPointsAndOffset" => {
-                let node = CopyPointsAndOffset::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "SortPointsX" => {
-                let node = SortPointsX::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "CreateShapeFromGroup" => {
-                let node = CreateShapeFromGroup::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "CreateShapeFromAllGroups" => {
-                let node = CreateShapeFromAllGroups::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "Translate" => {
-                let node = Translate::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "RandomTranslate" => {
-                let node = RandomTranslate::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "CopyPointsAndRandomOffset" => {
-                let node = CopyPointsAndRandomOffset::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "InstanceOnPoints" => {
-                let node = InstanceOnPoints::new();
-                self.add_subgraph_processor_node(Box::from(node))
-            },
-            "ExtrudeShape" => {
-                let node = ExtrudeShape::new();
-                self.add_processor_node(Box::from(node))
-            },
-            "GroupPoints" => {
-                let node = GroupPoints::new();
-                self.add_processor_node(Box::from(node))
-            },
-            &_ => panic!("Invalid Type-Name provided!")
-        }        
+        }
     }
+}
 
+fn find_group(world: &GeometryWorld, group_name: &str) -> Result<GroupHandle, HardeenError> {
+    world.get_group_by_name(group_name).ok_or_else(|| HardeenError::GroupDoesNotExist {
+        group_name: String::from(group_name)
+    })
 }
 
 macro_rules! parse_input_component {
     (SlottedInput, $number_of_inputs:expr) => {
         InputComponent::Slotted(SlottedInput::new($number_of_inputs))
     };
+    (OptionalSlottedInput, $number_of_inputs:expr) => {
+        InputComponent::Slotted(SlottedInput::with_optional_slots($number_of_inputs))
+    };
     (MultipleInput, $accept_zero:expr) => {
         InputComponent::Multiple(MultipleInput::new($accept_zero))
     };
@@ -111,25 +82,39 @@ macro_rules! parse_input_component_type {
     (SlottedInput, $number_of_inputs:expr) => {
         ProcessorInputType::Slotted{number_of_slots: $number_of_inputs}
     };
+    (OptionalSlottedInput, $number_of_inputs:expr) => {
+        ProcessorInputType::Slotted{number_of_slots: $number_of_inputs}
+    };
     (MultipleInput, $accept_zero:expr) => {
         ProcessorInputType::Multiple{zero_allowed: $accept_zero}
     };
 }
 
+/// Implements `Processor` for a processor struct. Each parameter is declared with its type and default
+/// value, optionally followed by metadata, e.g.
+/// `width => (f32, 10.0, { label: "Width", min: 0.0, step: 1.0 })`. The metadata keys are the builder
+/// methods of `ProcessorParameter`.
 macro_rules! create_processor {
-    ( $type:ident, ($input_component:tt, $input_component_parameter:expr), $inputs:expr, [ $( $param:ident => ($param_type:tt, $param_default:expr) ),* ] ) => {
+    ( $type:ident, ($input_component:tt, $input_component_parameter:expr), $inputs:expr, [ $( $param:ident => ($param_type:tt, $param_default:expr $(, { $( $meta_key:ident : $meta_value:expr ),* })? ) ),* ] ) => {
         impl $type {
             pub fn get_processor_type_info () -> ProcessorTypeInfo {               
 
                 ProcessorTypeInfo::new(
                     stringify!($type),
                     parse_input_component_type!($input_component, $input_component_parameter),
-                    vec![
+                    Self::get_parameter_declarations().to_vec()
+                )
+            }
+
+            fn get_parameter_declarations() -> &'static [ProcessorParameter] {
+                static P : OnceLock<Vec<ProcessorParameter>> = OnceLock::new();
+
+                P.get_or_init(|| vec![
                     $(
-                        ProcessorParameter { param_name: stringify!($param), param_type: stringify!($param_type) },
+                        ProcessorParameter::new(stringify!($param), <$param_type as ParameterValue>::to_parameter(&$param_default))
+                            $( $( .$meta_key($meta_value) )* )?,
                     )*
-                    ]
-                )
+                ])
             }
         }
 
@@ -154,12 +139,20 @@ macro_rules! create_processor {
             }
 
             #[allow(unused_variables)]
-            fn set_parameter(&mut self, parameter_name: &str, value: &str) -> Result<(), HardeenError> {
-                
+            fn set_parameter(&mut self, parameter_name: &str, value: Parameter) -> Result<(), HardeenError> {
+                let declaration = Self::get_parameter_declarations()
+                    .iter()
+                    .find(|declaration| declaration.param_name == parameter_name)
+                    .ok_or(HardeenError::NodeParameterDoesNotExist)?;
+
+                declaration.validate(&value)?;
+
                 match parameter_name {
                     $(
                         stringify!($param) => {
-                            self.$param = value.parse::<$param_type>().unwrap();
+                            if let Some(value) = <$param_type as ParameterValue>::from_parameter(value) {
+                                self.$param = value;
+                            }
                             Ok(())
                         },
                     )*
@@ -167,11 +160,11 @@ macro_rules! create_processor {
                 }
             }
 
-             fn get_parameter (&self, param: &str) -> Result<String, HardeenError> {
+             fn get_parameter (&self, param: &str) -> Result<Parameter, HardeenError> {
                 match param {
                     $(
                         stringify!($param) => {
-                            Ok(self.$param.to_string())
+                            Ok(self.$param.to_parameter())
                         },
                     )*
                     _ => Err(HardeenError::NodeParameterDoesNotExist)
@@ -180,12 +173,7 @@ macro_rules! create_processor {
 
 
             fn get_parameters (&self) -> &[ProcessorParameter] {
-                static P : &'static [ProcessorParameter] = &[
-                    $(
-                        ProcessorParameter { param_name: stringify!($param), param_type: stringify!($param_type) },
-                    )*
-                ];
-                P
+                Self::get_parameter_declarations()
             }
 
             fn is_parameter(&self, param: &str) -> bool {
@@ -224,7 +212,7 @@ impl CreateRectangle {
 
 impl BasicProcessor<GeometryWorld> for CreateRectangle {
 
-    fn run(&self, _input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, _input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = GeometryWorld::new();
         let rect = world.create_shape(true);
         let mut points = Vec::new();
@@ -240,14 +228,14 @@ impl BasicProcessor<GeometryWorld> for CreateRectangle {
 
         world.add_points_to_shape(points, &rect);
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(CreateRectangle, (MultipleInput,true), 0, [
-        width => (f32, 10.0),
-        height => (f32, 10.0),
-        position => (Position, Position(10.0, 10.0))
+        width => (f32, 10.0, { label: "Width", step: 1.0 }),
+        height => (f32, 5.0, { label: "Height", step: 1.0 }),
+        position => (Position, Position(0.0, 0.0), { label: "Center", description: "Center of the rectangle." })
 ]);
 
 
@@ -267,7 +255,7 @@ impl RandomTangents {
 
 impl BasicProcessor<GeometryWorld> for RandomTangents {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
         let mut rng = rand::thread_rng();
 
@@ -277,12 +265,12 @@ impl BasicProcessor<GeometryWorld> for RandomTangents {
             p.out_tangent = Position(offset.0 * -1.0, offset.1 * -1.0) + p.position;
         } );
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(RandomTangents, (SlottedInput,1), 1, [
-    strength => (f32, 2.0)
+    strength => (f32, 2.0, { label: "Strength", step: 0.1, description: "Maximum length of the random tangents." })
 ]);
 
 pub struct SmoothTangents {
@@ -301,23 +289,23 @@ impl SmoothTangents {
 
 impl BasicProcessor<GeometryWorld> for SmoothTangents {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         let mut iter = (*input[0]).get_shape_handle_iterator();
         while let Some(shape_handle) = iter.next() {
 
-            let shape = (*input[0]).get_shape(&shape_handle).unwrap();
+            let shape = (*input[0]).get_shape(&shape_handle)?;
             let vertices = shape.get_vertices();
 
             if vertices.len() < 3 {
                 continue;
             }
 
-            let generate_tangents = | handles: (&PointHandle,&PointHandle,&PointHandle)| {
-                let p1 = (*input)[0].get_point(handles.0).unwrap();
-                let p2 = (*input)[0].get_point(handles.1).unwrap();
-                let p3 = (*input)[0].get_point(handles.2).unwrap();
+            let generate_tangents = | handles: (&PointHandle,&PointHandle,&PointHandle)| -> Result<(), HardeenError> {
+                let p1 = (*input)[0].get_point(handles.0)?;
+                let p2 = (*input)[0].get_point(handles.1)?;
+                let p3 = (*input)[0].get_point(handles.2)?;
 
                 let mut updated_point = p2.clone();
 
@@ -327,26 +315,27 @@ impl BasicProcessor<GeometryWorld> for SmoothTangents {
                 updated_point.out_tangent = (p3.position - p1.position) * Position(self.strength, self.strength);
                 updated_point.in_tangent = (p1.position - p3.position) * Position(self.strength, self.strength);
 
-                world.set_point(handles.1, updated_point).expect("Point could not be set!");
+                world.set_point(handles.1, updated_point)?;
+                Ok(())
             };
 
             if shape.is_closed() {
                 iter::once(vertices.last().unwrap())
                     .chain(vertices.iter())
                     .chain(iter::once(vertices.first().unwrap()))
-                    .tuple_windows::<(_, _, _)>().for_each(generate_tangents);
+                    .tuple_windows::<(_, _, _)>().try_for_each(generate_tangents)?;
             }
             else {
-                vertices.iter().tuple_windows::<(_, _, _)>().for_each(generate_tangents);
+                vertices.iter().tuple_windows::<(_, _, _)>().try_for_each(generate_tangents)?;
             }
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(SmoothTangents,(SlottedInput,1), 1, [
-    strength => (f32, 2.0)
+    strength => (f32, 2.0, { label: "Strength", step: 0.1 })
 ]);
 
 pub struct ExtrudeShape {
@@ -369,7 +358,7 @@ fn solve_bezier_t(t: f32, p1: Position, c1: Position, c2: Position, p2: Position
 
 impl BasicProcessor<GeometryWorld> for ExtrudeShape {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         for shape_handle in (*input[0]).get_shape_handle_iterator() {
@@ -392,7 +381,7 @@ impl BasicProcessor<GeometryWorld> for ExtrudeShape {
                     //let strength_1 = solve_bezier_t(idx as f32 / shape.get_vertices().len() as f32 , Position(0.0, 0.0), Position(17.75, 14.0), Position(15.87, 17.46), Position(0.7, 4.68) ).length();
                     //let strength_2 = strength_1;
 
-                    let p = world.get_point(point_handle).unwrap();
+                    let p = world.get_point(point_handle)?;
                     let vertices = shape.get_vertices();
 
                     let mut tangent_1 = Position(0.0,0.0);
@@ -403,7 +392,7 @@ impl BasicProcessor<GeometryWorld> for ExtrudeShape {
                             tangent_1 = p.out_tangent;
                         }
                         else if let Some(p_next_handle) = vertices.get(idx+1) {
-                            let p_next = world.get_point(p_next_handle).unwrap();
+                            let p_next = world.get_point(p_next_handle)?;
 
                             if p_next.in_tangent == Position(0.0,0.0) {
                                 // linear Segment
@@ -421,7 +410,7 @@ impl BasicProcessor<GeometryWorld> for ExtrudeShape {
                             tangent_1 = p.in_tangent;
                         }
                         else if let Some(p_prev_handle) = vertices.get(idx-1) {
-                            let p_prev = world.get_point(p_prev_handle).unwrap();
+                            let p_prev = world.get_point(p_prev_handle)?;
 
                             if p_prev.out_tangent == Position(0.0,0.0) {
                                 tangent_2 = p.position - p_prev.position
@@ -452,18 +441,18 @@ impl BasicProcessor<GeometryWorld> for ExtrudeShape {
                 world.add_points_to_shape(vertices_1, &new_shape_1_handle);
                 world.add_points_to_shape(vertices_2, &new_shape_1_handle);
 
-                world.remove_shape(shape_handle.clone());
+                world.remove_shape(shape_handle.clone())?;
             }
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 
 }
 
 create_processor!(ExtrudeShape, (SlottedInput, 1), 1, [
-    min_thickness => (f32, 1.0),
-    max_thickness => (f32, 1.0)
+    min_thickness => (f32, 1.0, { label: "Min Thickness", step: 0.1 }),
+    max_thickness => (f32, 1.0, { label: "Max Thickness", step: 0.1 })
 ]);
 
 pub struct Scale {
@@ -482,7 +471,7 @@ impl Scale {
 
 impl BasicProcessor<GeometryWorld> for Scale {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         world.mutate_all_points(| p: &mut Point | {
@@ -490,13 +479,13 @@ impl BasicProcessor<GeometryWorld> for Scale {
             p.position.1 *= self.factor_y;
         } );
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(Scale, (SlottedInput,1), 1, [
-    factor_x => (f32, 1.2),
-    factor_y => (f32, 1.2)
+    factor_x => (f32, 1.2, { label: "Factor X", step: 0.1 }),
+    factor_y => (f32, 1.2, { label: "Factor Y", step: 0.1 })
 ]);
 
 pub struct ScatterPoints {
@@ -517,7 +506,7 @@ impl ScatterPoints {
 
 impl BasicProcessor<GeometryWorld> for ScatterPoints {
 
-    fn run(&self, _input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, _input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = GeometryWorld::new();
         let mut rng = rand::thread_rng();
 
@@ -531,14 +520,14 @@ impl BasicProcessor<GeometryWorld> for ScatterPoints {
             world.create_point(p);
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(ScatterPoints, (MultipleInput,true), 0, [
-    num_points => (u32, 10),
-    min_position => (Position, Position(-200.0,-200.0)),
-    max_position => (Position, Position(200.0,200.0))
+    num_points => (u32, 10, { label: "Number of Points", step: 1.0 }),
+    min_position => (Position, Position(-200.0,-200.0), { label: "Min Position", description: "Lower corner of the area the points are scattered in." }),
+    max_position => (Position, Position(200.0,200.0), { label: "Max Position", description: "Upper corner of the area the points are scattered in." })
 ]);
 
 
@@ -557,7 +546,7 @@ impl AddPoints {
 
 impl BasicProcessor<GeometryWorld> for AddPoints {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
 
         let mut world = match input.len() {
             0 => GeometryWorld::new(),
@@ -568,12 +557,12 @@ impl BasicProcessor<GeometryWorld> for AddPoints {
             world.create_point(Point::new_linear(*position));
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(AddPoints, (SlottedInput,1), 1, [
-    positions => ( PositionList, PositionList(Vec::new()) )
+    positions => ( PositionList, PositionList(Vec::new()), { label: "Positions" } )
 ]);
 
 pub struct Empty {
@@ -588,8 +577,8 @@ impl Empty {
 
 impl BasicProcessor<GeometryWorld> for Empty {
 
-    fn run(&self, _input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
-        Rc::from(GeometryWorld::new())
+    fn run(&self, _input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+        Ok(Shared::from(GeometryWorld::new()))
     }
 }
 
@@ -605,13 +594,13 @@ impl Merge {
 
 impl BasicProcessor<GeometryWorld> for Merge {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
         let mut handle_map : HashMap<PointHandle, PointHandle> = HashMap::new();
 
         let mut point_iter = (*input[1]).get_point_handle_iterator();
         while let Some(point_handle) = point_iter.next() {
-            let merged_point = (*input[1]).get_point(&point_handle).unwrap().clone();
+            let merged_point = (*input[1]).get_point(&point_handle)?.clone();
             let merged_handle = world.create_point(merged_point);
 
             handle_map.insert(point_handle, merged_handle);
@@ -619,7 +608,7 @@ impl BasicProcessor<GeometryWorld> for Merge {
 
         let mut shape_iter=(*input[1]).get_shape_handle_iterator();
         while let Some(shape_handle) = shape_iter.next() {
-            let shape = (*input[1]).get_shape(&shape_handle).unwrap();
+            let shape = (*input[1]).get_shape(&shape_handle)?;
             let merged_handle = world.create_shape(shape.is_closed());
 
             for old_point_handle in shape.get_vertices().iter() {
@@ -630,7 +619,7 @@ impl BasicProcessor<GeometryWorld> for Merge {
 
         let mut group_iter=(*input[1]).get_group_handle_iterator();
         while let Some(group_handle) = group_iter.next() {
-            let group = (*input[1]).get_group(&group_handle).unwrap();
+            let group = (*input[1]).get_group(&group_handle)?;
 
             let merged_handle = match world.get_group_by_name(&group.name) {
                 Some(group_handle) => {
@@ -653,7 +642,7 @@ impl BasicProcessor<GeometryWorld> for Merge {
             }*/
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
@@ -675,7 +664,7 @@ impl CopyPointsAndOffset {
 
 impl BasicProcessor<GeometryWorld> for CopyPointsAndOffset {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         let mut iter = (*input[0]).get_point_iterator();
@@ -685,13 +674,13 @@ impl BasicProcessor<GeometryWorld> for CopyPointsAndOffset {
             world.create_point(copied_point);
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 
 }
 
 create_processor!(CopyPointsAndOffset, (SlottedInput,1), 1, [
-    offset_position => (Position, Position(0.0,0.0))
+    offset_position => (Position, Position(0.0,0.0), { label: "Offset" })
 ]);
 
 pub struct CopyPointsAndRandomOffset {
@@ -718,11 +707,11 @@ impl CopyPointsAndRandomOffset {
 
 impl BasicProcessor<GeometryWorld> for CopyPointsAndRandomOffset {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
-        let group_handle = &(*input[0]).get_group_by_name(&self.group_name).unwrap();
-        let group = (*input[0]).get_group(&group_handle).unwrap();
+        let group_handle = find_group(&input[0], &self.group_name)?;
+        let group = (*input[0]).get_group(&group_handle)?;
 
         /*
         let mut iter = (&group.points).into_iter().map(|ph| {
@@ -735,7 +724,7 @@ impl BasicProcessor<GeometryWorld> for CopyPointsAndRandomOffset {
 
         for (c, point_handle) in group.points.iter().enumerate() {
 
-            let mut last_point = (*input[0]).get_point(&point_handle).unwrap().clone();
+            let mut last_point = (*input[0]).get_point(&point_handle)?.clone();
 
             let group = match self.group {
                 true => {
@@ -762,17 +751,17 @@ impl BasicProcessor<GeometryWorld> for CopyPointsAndRandomOffset {
             }
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 
 }
 
 create_processor!(CopyPointsAndRandomOffset, (SlottedInput,1), 1, [
-    min_offset => (Position, Position(0.0,0.0)),
-    max_offset => (Position, Position(0.0,0.0)),
-    group_name => (String, "all".to_string()),
-    group => (bool, true),
-    iterations => (u32, 1)
+    min_offset => (Position, Position(0.0,0.0), { label: "Min Offset" }),
+    max_offset => (Position, Position(0.0,0.0), { label: "Max Offset" }),
+    group_name => (String, "all".to_string(), { label: "Group", group_usage: GroupUsage::Reads }),
+    group => (bool, true, { label: "Group Copies", group_usage: GroupUsage::CreatesNumbered("cg") }),
+    iterations => (u32, 1, { label: "Iterations", step: 1.0 })
 ]);
 
 
@@ -792,11 +781,11 @@ impl SortPointsX {
 
 impl BasicProcessor<GeometryWorld> for SortPointsX {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
-        let group_handle = (*input)[0].get_group_by_name("all").unwrap();
+        let group_handle = find_group(&input[0], "all")?;
 
-        let group = world.get_group_mut(&group_handle).unwrap();
+        let group = world.get_group_mut(&group_handle)?;
 
         group.points.sort_by(|handle_p1, handle_p2| {
 
@@ -817,7 +806,7 @@ impl BasicProcessor<GeometryWorld> for SortPointsX {
         });
 
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
@@ -839,11 +828,11 @@ impl SortPointsY {
 
 impl BasicProcessor<GeometryWorld> for SortPointsY {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
-        let group_handle = (*input)[0].get_group_by_name("all").unwrap();
+        let group_handle = find_group(&input[0], "all")?;
 
-        let group = world.get_group_mut(&group_handle).unwrap();
+        let group = world.get_group_mut(&group_handle)?;
 
         group.points.sort_by(|handle_p1, handle_p2| {
 
@@ -864,7 +853,7 @@ impl BasicProcessor<GeometryWorld> for SortPointsY {
         });
 
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
@@ -887,23 +876,23 @@ impl CreateShapeFromGroup {
 
 impl BasicProcessor<GeometryWorld> for CreateShapeFromGroup {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
 
         let mut world = (*input[0]).clone();
 
         let shape_handle = world.create_shape(self.closed);
-        let group_handle = (*input[0]).get_group_by_name(&self.group_name).unwrap();
-        let group = (*input[0]).get_group(&group_handle).unwrap();
+        let group_handle = find_group(&input[0], &self.group_name)?;
+        let group = (*input[0]).get_group(&group_handle)?;
 
         world.add_points_to_shape(group.points.clone(), &shape_handle);
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(CreateShapeFromGroup, (SlottedInput,1), 1, [
-    group_name => (String, "all".to_string()),
-    closed => (bool, false)
+    group_name => (String, "all".to_string(), { label: "Group", group_usage: GroupUsage::Reads }),
+    closed => (bool, false, { label: "Closed" })
 ]);
 
 pub struct CreateShapeFromAllGroups {
@@ -921,14 +910,14 @@ impl CreateShapeFromAllGroups {
 
 impl BasicProcessor<GeometryWorld> for CreateShapeFromAllGroups {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         let mut iter = (*input[0]).get_group_handle_iterator();
 
         while let Some(group_handle) = iter.next() {
             let shape_handle = world.create_shape(self.closed);
-            let group = (*input[0]).get_group(&group_handle).unwrap();
+            let group = (*input[0]).get_group(&group_handle)?;
 
             if group.name == "all" {
                 continue;
@@ -937,12 +926,12 @@ impl BasicProcessor<GeometryWorld> for CreateShapeFromAllGroups {
             world.add_points_to_shape(group.points.clone(), &shape_handle);
         }
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(CreateShapeFromAllGroups, (SlottedInput,1), 1, [
-    closed => (bool, false)
+    closed => (bool, false, { label: "Closed" })
 ]);
 
 pub struct Translate {
@@ -962,21 +951,61 @@ impl Translate {
 
 impl BasicProcessor<GeometryWorld> for Translate {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
-        let group_handle = &world.get_group_by_name(&self.group_name).unwrap();
+        let group_handle = &find_group(&world, &self.group_name)?;
 
         world.mutate_all_points_in_group(group_handle, |p| {
             p.position = p.position + self.offset;
         });
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(Translate, (SlottedInput,1), 1, [
-    offset => (Position, Position(0.0,0.0)),
-    group_name => (String, "all".to_string())
+    offset => (Position, Position(0.0,0.0), { label: "Offset" }),
+    group_name => (String, "all".to_string(), { label: "Group", group_usage: GroupUsage::Reads })
+]);
+
+/// Moves points with a constant velocity, so that they are offset by `velocity` times the time of
+/// the frame that is evaluated.
+pub struct TranslateOverTime {
+    velocity: Position,
+    group_name: String
+}
+
+impl TranslateOverTime {
+    pub fn new() -> Self {
+        TranslateOverTime {
+            velocity: Position(0.0,0.0),
+            group_name: String::from("all")
+        }
+    }
+}
+
+impl BasicProcessor<GeometryWorld> for TranslateOverTime {
+
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+        let mut world = (*input[0]).clone();
+        let group_handle = &find_group(&world, &self.group_name)?;
+        let offset = self.velocity * context.get_time() as f32;
+
+        world.mutate_all_points_in_group(group_handle, |p| {
+            p.position += offset;
+        });
+
+        Ok(Shared::from(world))
+    }
+
+    fn is_time_dependent(&self) -> bool {
+        true
+    }
+}
+
+create_processor!(TranslateOverTime, (SlottedInput,1), 1, [
+    velocity => (Position, Position(0.0,0.0), { label: "Velocity", description: "Offset per second." }),
+    group_name => (String, "all".to_string(), { label: "Group", group_usage: GroupUsage::Reads })
 ]);
 
 pub struct RandomTranslate {
@@ -998,12 +1027,12 @@ impl RandomTranslate {
 
 impl BasicProcessor<GeometryWorld> for RandomTranslate {
 
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         let mut rng = rand::thread_rng();
 
-        let group_handle = &world.get_group_by_name(&self.group_name).unwrap();
+        let group_handle = &find_group(&world, &self.group_name)?;
 
         world.mutate_all_points_in_group(group_handle, |p| {
             let dx_range : f32 = self.max_offset.0 - self.min_offset.0;
@@ -1016,14 +1045,14 @@ impl BasicProcessor<GeometryWorld> for RandomTranslate {
             p.position = p.position + Position(dx,dy);
         });
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(RandomTranslate, (SlottedInput,1), 1, [
-    min_offset => (Position, Position(0.0,0.0)),
-    max_offset => (Position, Position(0.0,0.0)),
-    group_name => (String, String::from("all"))
+    min_offset => (Position, Position(0.0,0.0), { label: "Min Offset" }),
+    max_offset => (Position, Position(0.0,0.0), { label: "Max Offset" }),
+    group_name => (String, String::from("all"), { label: "Group", group_usage: GroupUsage::Reads })
 ]);
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -1047,28 +1076,34 @@ impl GroupPoints {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl BasicProcessor<GeometryWorld> for GroupPoints {
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
         let gil = Python::acquire_gil();
         let py = gil.python();
 
         if self.group_name == "" {
-            return Rc::from(world);
+            return Ok(Shared::from(world));
         }
 
         let new_group_handle = world.create_group(&self.group_name);
         let mut points = Vec::new();
 
         for (idx, point_handle) in world.get_all_points().iter().enumerate() {
-            let point = world.get_point(point_handle).unwrap();
+            let point = world.get_point(point_handle)?;
+
+            let python_error = |error: cpython::PyErr| HardeenError::ProcessingFailed {
+                message: format!("Could not evaluate \"{}\": {:?}", self.py_condition, error)
+            };
 
             let locals = cpython::PyDict::new(py);
-            locals.set_item(py, 'N'.to_string(), idx + 1).unwrap();
-            locals.set_item(py, 'X'.to_string(), point.position.1).unwrap();
-            locals.set_item(py, 'Y'.to_string(), point.position.0).unwrap();
+            locals.set_item(py, 'N'.to_string(), idx + 1).map_err(python_error)?;
+            locals.set_item(py, 'X'.to_string(), point.position.1).map_err(python_error)?;
+            locals.set_item(py, 'Y'.to_string(), point.position.0).map_err(python_error)?;
 
-            let result: bool = py.eval(&self.py_condition, None, Some(&locals)).unwrap().extract(py).unwrap();
+            let result: bool = py.eval(&self.py_condition, None, Some(&locals))
+                .and_then(|result| result.extract(py))
+                .map_err(python_error)?;
 
             if result == true {
                 points.push((*point_handle).clone());
@@ -1076,22 +1111,22 @@ impl BasicProcessor<GeometryWorld> for GroupPoints {
         }
         world.add_points_to_group(points, &new_group_handle);
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 #[cfg(target_arch = "wasm32")]
 impl BasicProcessor<GeometryWorld> for GroupPoints {
-    fn run(&self, input : Vec<Rc<GeometryWorld>>) -> Rc<GeometryWorld> {
+    fn run(&self, input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = (*input[0]).clone();
 
-        Rc::from(world)
+        Ok(Shared::from(world))
     }
 }
 
 create_processor!(GroupPoints, (SlottedInput,1), 1, [
-    group_name => (String, String::from("")),
-    py_condition => (String, String::from("False"))
+    group_name => (String, String::from(""), { label: "Group", group_usage: GroupUsage::Creates }),
+    py_condition => (String, String::from("False"), { label: "Condition", description: "Python expression deciding whether a point is added to the group." })
 ]);
 
 
@@ -1108,28 +1143,603 @@ impl InstanceOnPoints {
 }
 
 impl SubgraphProcessor<GeometryWorld> for InstanceOnPoints {
-    fn run(&self, input : Vec<Rc<GeometryWorld>>, subgraph: &Graph<GeometryWorld>) -> Rc<GeometryWorld> {
+    fn run(&self, input : GraphInputs<GeometryWorld>, subgraph: &Graph<GeometryWorld>, context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
         let mut world = GeometryWorld::new();
         
         let instance_point_world = &(*input[0]);
 
         if subgraph.is_output_node_set() {
-            for instance_point in instance_point_world.get_point_iterator() {
+            let instance_positions: Vec<Position> = instance_point_world.get_point_iterator().map(|p| p.position).collect();
 
-                let mut subgraph_result = (*subgraph.process_graph_output(false).unwrap()).clone();
+            // Every instance is evaluated on its own and without caches, so that subgraphs with random
+            // processors give each instance a different result.
+            let instances = map_parallel(instance_positions, |instance_position| -> Result<GeometryWorld, HardeenError> {
+                let mut subgraph_result = (*subgraph.process_graph_output_with_inputs(&input, context, false)?).clone();
 
                 subgraph_result.mutate_all_points( |p| {
-                    p.position = p.position + instance_point.position;
+                    p.position += instance_position;
                 });
 
-                world.merge(&subgraph_result);
+                Ok(subgraph_result)
+            });
+
+            for instance in instances.into_iter() {
+                world.merge(&instance?);
             }
         }
 
-        Rc::new(world)
+        Ok(Shared::new(world))
     }
 }
 
 create_processor!(InstanceOnPoints, (SlottedInput,1), 1, [
-    group_name => (String, String::from("all"))
-]);
\ No newline at end of file
+    group_name => (String, String::from("all"), { label: "Group", group_usage: GroupUsage::Reads })
+]);
+
+/// Wraps a subgraph that gets the inputs of the subnet node through its `GraphInput` nodes.
+pub struct Subnet {}
+
+impl Subnet {
+    pub fn new() -> Self {
+        Subnet {}
+    }
+}
+
+impl SubgraphProcessor<GeometryWorld> for Subnet {
+    fn run(&self, input : GraphInputs<GeometryWorld>, subgraph: &Graph<GeometryWorld>, context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+        subgraph.process_graph_output_with_inputs(&input, context, true)
+    }
+}
+
+create_processor!(Subnet, (OptionalSlottedInput, 4), 4, []);
+
+/// Outputs the input of the enclosing subgraph node at `index`.
+pub struct GraphInput {
+    index: u32
+}
+
+impl GraphInput {
+    pub fn new() -> Self {
+        GraphInput {
+            index: 0
+        }
+    }
+}
+
+impl BasicProcessor<GeometryWorld> for GraphInput {
+    fn run(&self, _input : Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+        Err(HardeenError::GraphInputNotConnected { index: self.index as usize })
+    }
+
+    fn get_graph_input_index(&self) -> Option<usize> {
+        Some(self.index as usize)
+    }
+}
+
+create_processor!(GraphInput, (SlottedInput, 0), 0, [
+    index => (u32, 0, { label: "Input" })
+]);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn save_and_load_graph() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let removed = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let add_points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.remove_node(removed.clone()).unwrap();
+
+        graph.get_node_mut(&rectangle).unwrap().set_parameter("width", Parameter::Float(42.5)).unwrap();
+        graph.get_node_mut(&add_points).unwrap().set_parameter_from_str("positions", "1,2;3.5,-4;").unwrap();
+        graph.connect(&rectangle, &add_points).unwrap();
+        graph.connect(&add_points, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+        graph.expose_parameter("width", &rectangle, "width").unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let sub_rectangle = subgraph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        subgraph.set_output_node_handle(sub_rectangle.clone()).unwrap();
+
+        let json = graph.save_to_json().unwrap();
+        let mut loaded = Graph::load_from_json(&json, &registry).unwrap();
+
+        assert_eq!(loaded.get_node(&rectangle).unwrap().get_parameter("width").unwrap(), Parameter::Float(42.5));
+        assert_eq!(loaded.get_node(&add_points).unwrap().get_parameter("positions").unwrap().to_string(), "1,2;3.5,-4;");
+        assert!(loaded.get_node(&add_points).unwrap().get_all_input_handles() == vec![rectangle.clone()]);
+        assert!(loaded.get_node(&rectangle).unwrap().get_all_outputs().contains(&add_points));
+        assert!(loaded.get_output_node_handle() == Some(instance.clone()));
+        assert!(loaded.get_node(&removed).is_err());
+
+        let loaded_subgraph = loaded.get_subgraph_for_node(&instance).unwrap();
+        assert!(loaded_subgraph.get_output_node_handle() == Some(sub_rectangle));
+
+        loaded.set_exposed_parameter("width", Parameter::Float(7.0)).unwrap();
+        assert_eq!(loaded.get_node(&rectangle).unwrap().get_parameter("width").unwrap(), Parameter::Float(7.0));
+
+        let original_output = graph.process_graph_output(true).unwrap();
+        let loaded_output = loaded.process_graph_output(true).unwrap();
+        assert_eq!(original_output.get_point_count(), loaded_output.get_point_count());
+
+        assert_eq!(Graph::load_from_json(&loaded.save_to_json().unwrap(), &registry).unwrap().save_to_json().unwrap(),
+            loaded.save_to_json().unwrap());
+    }
+
+    #[test]
+    fn load_rejects_newer_versions() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let graph : Graph<GeometryWorld> = Graph::new();
+        let json = graph.save_to_json().unwrap().replace(
+            &format!("\"version\":{}", PROJECT_FILE_VERSION),
+            &format!("\"version\":{}", PROJECT_FILE_VERSION + 1));
+
+        match Graph::load_from_json(&json, &registry) {
+            Err(HardeenError::ProjectFileVersionUnsupported) => {},
+            _ => panic!("Expected unsupported version!")
+        }
+    }
+
+    struct Doubler {}
+
+    impl Processor<GeometryWorld> for Doubler {
+        fn number_inputs(&self) -> usize { 1 }
+        fn build_input_component(&self) -> InputComponent<NodeHandle<GeometryWorld>> {
+            InputComponent::Slotted(SlottedInput::new(1))
+        }
+        fn set_parameter(&mut self, _param: &str, _value: Parameter) -> Result<(), HardeenError> {
+            Err(HardeenError::NodeParameterDoesNotExist)
+        }
+        fn get_parameter(&self, _param: &str) -> Result<Parameter, HardeenError> {
+            Err(HardeenError::NodeParameterDoesNotExist)
+        }
+        fn get_parameters(&self) -> &[ProcessorParameter] { &[] }
+        fn is_parameter(&self, _param: &str) -> bool { false }
+        fn get_processor_name(&self) -> &'static str { "Doubler" }
+    }
+
+    impl BasicProcessor<GeometryWorld> for Doubler {
+        fn run(&self, input: Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+            let mut world = (*input[0]).clone();
+            world.merge(&input[0]);
+            Ok(Shared::from(world))
+        }
+    }
+
+    #[test]
+    fn register_custom_processor() {
+        let mut registry = ProcessorRegistry::with_builtin_processors();
+        registry.register_basic_processor(
+            ProcessorTypeInfo::new("Doubler", ProcessorInputType::Slotted { number_of_slots: 1 }, vec![]),
+            || Box::new(Doubler {}));
+
+        assert!(registry.get_processor_types().iter().any(|type_info| type_info.get_name() == "Doubler"));
+
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let doubler = graph.add_processor_node_by_type(&registry, "Doubler").unwrap();
+        graph.connect(&rectangle, &doubler).unwrap();
+        graph.set_output_node_handle(doubler).unwrap();
+
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 8);
+
+        let loaded = Graph::load_from_json(&graph.save_to_json().unwrap(), &registry).unwrap();
+        assert_eq!(loaded.process_graph_output(true).unwrap().get_point_count(), 8);
+    }
+
+    #[test]
+    fn unknown_processor_type() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        match graph.add_processor_node_by_type(&registry, "DoesNotExist") {
+            Err(HardeenError::NodeTypeInvalid) => {},
+            _ => panic!("Expected NodeTypeInvalid!")
+        }
+    }
+
+    #[test]
+    fn set_typed_parameters() {
+        let mut scatter = ScatterPoints::new();
+
+        scatter.set_parameter("num_points", Parameter::UnsignedInteger(3)).unwrap();
+        scatter.set_parameter("min_position", Parameter::Position(-1.0, 2.0)).unwrap();
+        assert_eq!(scatter.get_parameter("num_points").unwrap(), Parameter::UnsignedInteger(3));
+        assert_eq!(scatter.get_parameter("min_position").unwrap(), Parameter::Position(-1.0, 2.0));
+
+        match scatter.set_parameter("num_points", Parameter::Float(3.0)) {
+            Err(HardeenError::ParameterTypeMismatch { expected: ParameterType::UnsignedInteger, found: ParameterType::Float, .. }) => {},
+            _ => panic!("Expected a type mismatch!")
+        }
+        assert_eq!(scatter.get_parameter("num_points").unwrap(), Parameter::UnsignedInteger(3));
+        assert!(scatter.set_parameter("does_not_exist", Parameter::Boolean(true)).is_err());
+    }
+
+    #[test]
+    fn parameter_declarations() {
+        let type_info = CreateRectangle::get_processor_type_info();
+        let width = type_info.get_parameters().iter().find(|p| p.param_name == "width").unwrap();
+
+        assert_eq!(width.label, "Width");
+        assert_eq!(width.default_value, Parameter::Float(10.0));
+
+        let rectangle = CreateRectangle::new();
+        for declaration in rectangle.get_parameters().iter() {
+            assert_eq!(rectangle.get_parameter(declaration.param_name).unwrap(), declaration.default_value);
+        }
+    }
+
+    pub struct Bounded {
+        count: u32,
+        mode: String
+    }
+
+    impl BasicProcessor<GeometryWorld> for Bounded {
+        fn run(&self, input: Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+            Ok(input[0].clone())
+        }
+    }
+
+    create_processor!(Bounded, (SlottedInput, 1), 1, [
+        count => (u32, 2, { min: 1.0, max: 4.0 }),
+        mode => (String, String::from("a"), { options: &["a", "b"] })
+    ]);
+
+    #[test]
+    fn load_values_outside_of_bounds() {
+        let mut bounded = Bounded::default();
+        match bounded.set_parameter("count", Parameter::UnsignedInteger(9)) {
+            Err(HardeenError::ParameterOutOfRange { max: Some(_), .. }) => {},
+            _ => panic!("Expected the count to be out of range!")
+        }
+        assert_eq!(bounded.get_parameter("count").unwrap(), Parameter::UnsignedInteger(2));
+
+        let mut registry = ProcessorRegistry::with_builtin_processors();
+        registry.register_basic_processor(Bounded::get_processor_type_info(), || Box::new(Bounded::default()));
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+        let node = graph.add_processor_node_by_type(&registry, "Bounded").unwrap();
+
+        let json = graph.save_to_json().unwrap()
+            .replace("\"count\":\"2\"", "\"count\":\"9\"")
+            .replace("\"mode\":\"a\"", "\"mode\":\"c\"");
+        let loaded = Graph::load_from_json(&json, &registry).unwrap();
+        assert_eq!(loaded.get_node(&node).unwrap().get_parameter("count").unwrap(), Parameter::UnsignedInteger(4));
+        assert_eq!(loaded.get_node(&node).unwrap().get_parameter("mode").unwrap(), Parameter::String(String::from("a")));
+    }
+
+    #[test]
+    fn report_failing_node() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.connect(&rectangle, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let subgraph_handle = graph.get_subgraph_handle(&instance).unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let sub_rectangle = subgraph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let translate = subgraph.add_processor_node_by_type(&registry, "Translate").unwrap();
+        subgraph.connect(&sub_rectangle, &translate).unwrap();
+        subgraph.get_node_mut(&translate).unwrap().set_parameter("group_name", Parameter::String(String::from("missing"))).unwrap();
+        subgraph.set_output_node_handle(translate.clone()).unwrap();
+
+        match graph.process_graph_output(true) {
+            Err(HardeenError::ErrorProcessingNode(node_error)) => {
+                assert_eq!(node_error.subgraph_path, vec![subgraph_handle.get()]);
+                assert_eq!(node_error.node, translate.get());
+                assert_eq!(node_error.processor_name, "Translate");
+
+                match *node_error.error {
+                    HardeenError::GroupDoesNotExist { group_name } => assert_eq!(group_name, "missing"),
+                    _ => panic!("Expected a missing group!")
+                }
+            },
+            _ => panic!("Expected the evaluation to fail!")
+        }
+    }
+
+    struct CountRuns {
+        runs: Shared<AtomicUsize>
+    }
+
+    impl Processor<GeometryWorld> for CountRuns {
+        fn number_inputs(&self) -> usize { 0 }
+        fn build_input_component(&self) -> InputComponent<NodeHandle<GeometryWorld>> {
+            InputComponent::Multiple(MultipleInput::new(true))
+        }
+        fn set_parameter(&mut self, _param: &str, _value: Parameter) -> Result<(), HardeenError> {
+            Err(HardeenError::NodeParameterDoesNotExist)
+        }
+        fn get_parameter(&self, _param: &str) -> Result<Parameter, HardeenError> {
+            Err(HardeenError::NodeParameterDoesNotExist)
+        }
+        fn get_parameters(&self) -> &[ProcessorParameter] { &[] }
+        fn is_parameter(&self, _param: &str) -> bool { false }
+        fn get_processor_name(&self) -> &'static str { "CountRuns" }
+    }
+
+    impl BasicProcessor<GeometryWorld> for CountRuns {
+        fn run(&self, input: Vec<Shared<GeometryWorld>>, _context: &EvaluationContext) -> Result<Shared<GeometryWorld>, HardeenError> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+
+            let mut world = GeometryWorld::new();
+            for other in input.iter() {
+                world.merge(other);
+            }
+            Ok(Shared::from(world))
+        }
+    }
+
+    #[test]
+    fn shared_inputs_run_once() {
+        let runs = Shared::new(AtomicUsize::new(0));
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let source = graph.add_processor_node(Box::new(CountRuns { runs: runs.clone() }));
+        let mut layer = vec![source];
+
+        for _ in 0..10 {
+            let left = graph.add_processor_node(Box::new(CountRuns { runs: runs.clone() }));
+            let right = graph.add_processor_node(Box::new(CountRuns { runs: runs.clone() }));
+            for node in layer.iter() {
+                graph.connect(node, &left).unwrap();
+                graph.connect(node, &right).unwrap();
+            }
+            layer = vec![left, right];
+        }
+
+        let sink = graph.add_processor_node(Box::new(CountRuns { runs: runs.clone() }));
+        graph.connect(&layer[0], &sink).unwrap();
+        graph.connect(&layer[1], &sink).unwrap();
+        graph.set_output_node_handle(sink).unwrap();
+
+        graph.process_graph_output(false).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 22);
+
+        graph.process_graph_output(false).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 44);
+
+        graph.process_graph_output(true).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 66);
+
+        graph.process_graph_output(true).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 66);
+    }
+
+    #[test]
+    fn evaluate_deep_graph() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let mut last = rectangle.clone();
+
+        for _ in 0..50000 {
+            let next = graph.add_processor_node_by_type(&registry, "SortPointsX").unwrap();
+            graph.connect(&last, &next).unwrap();
+            last = next;
+        }
+
+        graph.set_output_node_handle(last).unwrap();
+
+        assert_eq!(graph.process_graph_output(false).unwrap().get_point_count(), 4);
+        graph.invalidate_cache(&rectangle);
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 4);
+    }
+
+    #[test]
+    fn reuse_outputs_for_previous_parameters() {
+        let runs = Shared::new(AtomicUsize::new(0));
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let counter = graph.add_processor_node(Box::new(CountRuns { runs: runs.clone() }));
+        graph.connect(&rectangle, &counter).unwrap();
+        graph.set_output_node_handle(counter.clone()).unwrap();
+
+        graph.process_graph_output(true).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        graph.get_node_mut(&rectangle).unwrap().set_parameter("width", Parameter::Float(20.0)).unwrap();
+        graph.process_graph_output(true).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        graph.get_node_mut(&rectangle).unwrap().set_parameter("width", Parameter::Float(10.0)).unwrap();
+        graph.process_graph_output(true).unwrap();
+        graph.get_node_mut(&rectangle).unwrap().set_parameter("width", Parameter::Float(20.0)).unwrap();
+        graph.process_graph_output(true).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        graph.invalidate_cache(&rectangle);
+        graph.process_graph_output(true).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn subgraph_changes_reach_parent() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.get_node_mut(&points).unwrap().set_parameter_from_str("positions", "0,0;").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let rectangle = subgraph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        subgraph.set_output_node_handle(rectangle.clone()).unwrap();
+
+        let before = graph.process_graph_output(true).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        subgraph.get_node_mut(&rectangle).unwrap().set_parameter("width", Parameter::Float(20.0)).unwrap();
+
+        let after = graph.process_graph_output(true).unwrap();
+        assert!(before.get_bounding_rect() != after.get_bounding_rect());
+    }
+
+    #[test]
+    fn profile_nodes_and_subgraphs() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.get_node_mut(&points).unwrap().set_parameter_from_str("positions", "0,0;10,0;20,0;").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let subgraph_handle = graph.get_subgraph_handle(&instance).unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let rectangle = subgraph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        subgraph.set_output_node_handle(rectangle.clone()).unwrap();
+
+        let (result, profile) = graph.process_graph_output_with_profile(&EvaluationContext::default(), true);
+        assert!(result.is_ok());
+        assert_eq!(profile.nodes.len(), 4);
+
+        let points_profile = profile.get_node_profile(&[], points.get()).unwrap();
+        assert!(!points_profile.cache_hit);
+        assert_eq!(points_profile.output_size.points, 3);
+
+        let instance_profile = profile.get_node_profile(&[], instance.get()).unwrap();
+        assert_eq!(instance_profile.output_size.points, 12);
+        assert_eq!(instance_profile.output_size.shapes, 3);
+
+        let rectangle_profile = profile.get_node_profile(&[subgraph_handle.get()], rectangle.get()).unwrap();
+        assert_eq!(rectangle_profile.processor_name, "CreateRectangle");
+        assert_eq!(rectangle_profile.runs, 3);
+        assert_eq!(rectangle_profile.output_size.points, 4);
+
+        let (_, profile) = graph.process_graph_output_with_profile(&EvaluationContext::default(), true);
+        assert_eq!(profile.nodes.len(), 1);
+        assert!(profile.get_node_profile(&[], instance.get()).unwrap().cache_hit);
+    }
+
+    #[test]
+    fn instance_nested_subgraphs() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.get_node_mut(&points).unwrap().set_parameter_from_str("positions", "0,0;100,0;200,0;").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let sub_points = subgraph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let sub_empty = subgraph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let nested_instance = subgraph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        subgraph.get_node_mut(&sub_points).unwrap().set_parameter_from_str("positions", "0,0;0,50;").unwrap();
+        subgraph.connect(&sub_empty, &sub_points).unwrap();
+        subgraph.connect(&sub_points, &nested_instance).unwrap();
+        subgraph.set_output_node_handle(nested_instance.clone()).unwrap();
+
+        let nested_subgraph = subgraph.get_subgraph_for_node(&nested_instance).unwrap();
+        let rectangle = nested_subgraph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        nested_subgraph.set_output_node_handle(rectangle).unwrap();
+
+        let output = graph.process_graph_output(false).unwrap();
+        assert_eq!(output.get_point_count(), 24);
+        assert_eq!(output.get_bounding_rect(), graph.process_graph_output(true).unwrap().get_bounding_rect());
+    }
+
+    #[test]
+    fn instances_are_evaluated_separately() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.get_node_mut(&points).unwrap().set_parameter_from_str("positions", "0,0;0,0;").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let scatter = subgraph.add_processor_node_by_type(&registry, "ScatterPoints").unwrap();
+        subgraph.set_output_node_handle(scatter).unwrap();
+
+        let output = graph.process_graph_output(true).unwrap();
+        let positions: Vec<Position> = output.get_point_iterator().map(|p| p.position).collect();
+        assert_eq!(positions.len(), 20);
+        assert!(positions[..10] != positions[10..]);
+    }
+
+    #[test]
+    fn save_after_removing_exposed_and_output_nodes() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let scale = graph.add_processor_node_by_type(&registry, "Scale").unwrap();
+        graph.connect(&rectangle, &scale).unwrap();
+        graph.set_output_node_handle(scale.clone()).unwrap();
+        graph.expose_parameter("width", &rectangle, "width").unwrap();
+
+        graph.remove_node(rectangle.clone()).unwrap();
+        graph.remove_node(scale.clone()).unwrap();
+        assert!(graph.get_output_node_handle().is_none());
+        assert!(graph.get_exposed_parameters().is_empty());
+
+        let loaded = Graph::load_from_json(&graph.save_to_json().unwrap(), &registry).unwrap();
+        assert!(loaded.get_output_node_handle().is_none());
+        assert!(loaded.get_exposed_parameters().is_empty());
+    }
+
+    #[test]
+    fn outputs_are_cached_per_time() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph : Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let subnet = graph.add_processor_node_by_type(&registry, "Subnet").unwrap();
+        graph.connect(&rectangle, &subnet).unwrap();
+        graph.set_output_node_handle(subnet.clone()).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&subnet).unwrap();
+        let input = subgraph.add_processor_node_by_type(&registry, "GraphInput").unwrap();
+        let translate = subgraph.add_processor_node_by_type(&registry, "TranslateOverTime").unwrap();
+        subgraph.connect(&input, &translate).unwrap();
+        subgraph.set_output_node_handle(translate.clone()).unwrap();
+        subgraph.get_node_mut(&translate).unwrap().set_parameter_from_str("velocity", "10,0").unwrap();
+
+        let start = EvaluationContext::new(0.0, 24.0);
+        let later = start.at_frame(48.0);
+        assert_eq!(later.get_time(), 2.0);
+
+        let output = graph.process_graph_output_in_context(&start, true).unwrap();
+        let later_output = graph.process_graph_output_in_context(&later, true).unwrap();
+        assert_eq!(later_output.get_bounding_rect().0.0 - output.get_bounding_rect().0.0, 20.0);
+        assert!(graph.get_output_fingerprint(&start).unwrap() != graph.get_output_fingerprint(&later).unwrap());
+
+        assert!(Shared::ptr_eq(&output, &graph.process_graph_output_in_context(&start, true).unwrap()));
+        assert!(Shared::ptr_eq(&later_output, &graph.process_graph_output_in_context(&later, true).unwrap()));
+
+        graph.set_output_node_handle(rectangle.clone()).unwrap();
+        assert_eq!(graph.get_output_fingerprint(&start).unwrap(), graph.get_output_fingerprint(&later).unwrap());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn graph_is_thread_safe() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        assert_send_sync::<Graph<GeometryWorld>>();
+    }
+}
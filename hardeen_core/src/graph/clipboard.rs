@@ -0,0 +1,223 @@
+//! # Clipboard
+//!
+//! A `GraphClipboard` holds copies of a selection of nodes in the same form `GraphDocument` stores
+//! them. Only connections between the selected nodes are kept, and every copied subgraph
+//! processor brings along a copy of its subgraph. Pasting creates new nodes, so the clipboard can
+//! be pasted several times and into any graph, including the graph it was copied from.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::vec::Vec;
+
+use super::*;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClipboardNode {
+    /// The handle the node had in the graph it was copied from. Inputs refer to these handles.
+    pub handle: HandleDocument,
+    pub node: NodeDocument,
+    pub subgraph: Option<GraphDocument>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GraphClipboard {
+    pub nodes: Vec<ClipboardNode>,
+}
+
+impl GraphClipboard {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<T: Serialize> Graph<T> {
+    /// Copies the given nodes, their connections among each other and their subgraphs.
+    pub fn copy_nodes(&self, node_handles: &[NodeHandle<T>]) -> Result<GraphClipboard, HardeenError> {
+        let selection: HashSet<HandleDocument> = node_handles.iter().map(HandleDocument::from_handle).collect();
+        let mut clipboard = GraphClipboard::default();
+
+        for node_handle in node_handles.iter().unique() {
+            let node = self.get_node(node_handle)?;
+            let mut node_document = node.to_document();
+
+            for input in node_document.inputs.iter_mut() {
+                if input.is_some_and(|input| !selection.contains(&input)) {
+                    *input = None;
+                }
+            }
+
+            let subgraph = match node.get_processor_component() {
+                ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => {
+                    node_document.subgraph = None;
+                    Some(self.get_subgraph(subgraph_handle)?.to_document())
+                }
+                ProcessorComponent::BasicProcessor(_) => None,
+            };
+
+            clipboard.nodes.push(ClipboardNode {
+                handle: HandleDocument::from_handle(node_handle),
+                node: node_document,
+                subgraph,
+            });
+        }
+
+        Ok(clipboard)
+    }
+
+    /// Adds the nodes of a clipboard to this graph and returns the handles of the new nodes by the
+    /// handles they were copied from. Nodes keep their names unless the name is already taken, in
+    /// which case they are numbered anew. If any node cannot be created, the graph is left as it was.
+    pub fn paste_nodes(
+        &mut self,
+        clipboard: &GraphClipboard,
+        registry: &ProcessorRegistry<T>,
+    ) -> Result<HashMap<NodeHandle<T>, NodeHandle<T>>, HardeenError> {
+        let create_processor = |processor_type: &str| registry.create_processor(processor_type);
+
+        let mut added_subgraphs = Vec::new();
+        let mut nodes = Vec::new();
+
+        for clipboard_node in clipboard.nodes.iter() {
+            let result = self.node_from_clipboard(clipboard_node, &create_processor, &mut added_subgraphs);
+
+            match result {
+                Ok(node) => nodes.push((clipboard_node, node)),
+                Err(error) => {
+                    for subgraph_handle in added_subgraphs {
+                        self.subgraphs.remove_entry(subgraph_handle)?;
+                    }
+                    return Err(error);
+                }
+            }
+        }
+
+        let mut handle_mapping = HashMap::new();
+
+        for (clipboard_node, mut node) in nodes {
+            let name = match node.get_name() {
+                name if !name.is_empty() && !name.contains('/') && !self.node_names.contains_key(name) => String::from(name),
+                name => {
+                    let base_name = match name.trim_end_matches(|c: char| c.is_ascii_digit()) {
+                        "" => String::from(node.get_processor_name()),
+                        base_name => String::from(base_name),
+                    };
+                    self.get_unique_node_name(&base_name)
+                }
+            };
+            node.set_name(name.clone());
+
+            let node_handle = self.nodes.add_entry(node);
+            self.node_names.insert(name, node_handle.clone());
+            handle_mapping.insert(clipboard_node.handle.to_handle(), node_handle);
+        }
+
+        for clipboard_node in clipboard.nodes.iter() {
+            let to = &handle_mapping[&clipboard_node.handle.to_handle()];
+
+            for (slot_number, input) in clipboard_node.node.inputs.iter().enumerate() {
+                if let Some(from) = input.and_then(|input| handle_mapping.get(&input.to_handle())) {
+                    self.connect_to_slot(from, to, slot_number)?;
+                }
+            }
+        }
+
+        Ok(handle_mapping)
+    }
+
+    /// Copies the given nodes and pastes them into this graph right away.
+    pub fn duplicate_nodes(
+        &mut self,
+        node_handles: &[NodeHandle<T>],
+        registry: &ProcessorRegistry<T>,
+    ) -> Result<HashMap<NodeHandle<T>, NodeHandle<T>>, HardeenError> {
+        let clipboard = self.copy_nodes(node_handles)?;
+        self.paste_nodes(&clipboard, registry)
+    }
+
+    fn node_from_clipboard<F>(
+        &mut self,
+        clipboard_node: &ClipboardNode,
+        create_processor: &F,
+        added_subgraphs: &mut Vec<SubgraphHandle<T>>,
+    ) -> Result<Node<T>, HardeenError>
+    where
+        F: Fn(&str) -> Result<BoxedProcessor<T>, HardeenError>,
+    {
+        let mut node_document = clipboard_node.node.clone();
+
+        if let Some(subgraph_document) = &clipboard_node.subgraph {
+            let subgraph_handle = self.subgraphs.add_entry(Graph::from_document(subgraph_document, create_processor)?);
+            node_document.subgraph = Some(HandleDocument::from_handle(&subgraph_handle));
+            added_subgraphs.push(subgraph_handle);
+        }
+
+        self.node_from_document(&node_document, create_processor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::GeometryWorld;
+    use crate::project::HardeenProject;
+
+    #[test]
+    fn duplicate_nodes_with_connections_and_subgraphs() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph: Graph<GeometryWorld> = Graph::new();
+
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.connect(&empty, &rectangle).unwrap();
+        graph.connect_to_slot(&rectangle, &instance, 0).unwrap();
+        graph.get_node_mut(&rectangle).unwrap().set_parameter("width", Parameter::Float(3.0)).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let points = subgraph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        subgraph.set_output_node_handle(points).unwrap();
+
+        let mapping = graph.duplicate_nodes(&[rectangle.clone(), instance.clone()], &registry).unwrap();
+        assert_eq!(mapping.len(), 2);
+
+        let new_rectangle = &mapping[&rectangle];
+        let new_instance = &mapping[&instance];
+        assert!(new_rectangle != &rectangle);
+        assert!(graph.get_node(new_rectangle).unwrap().get_parameter("width").unwrap() == Parameter::Float(3.0));
+        assert_eq!(graph.get_node(new_rectangle).unwrap().get_name(), "CreateRectangle2");
+        assert!(graph.get_node(new_rectangle).unwrap().get_all_input_handles().is_empty());
+        assert!(graph.get_node(new_instance).unwrap().get_all_input_handles() == vec![new_rectangle.clone()]);
+
+        let subgraph_handle = graph.get_subgraph_handle(&instance).unwrap();
+        let new_subgraph_handle = graph.get_subgraph_handle(new_instance).unwrap();
+        assert!(subgraph_handle != new_subgraph_handle);
+        assert!(graph.get_subgraph(&new_subgraph_handle).unwrap().get_node_handle_by_name("AddPoints1").is_some());
+        assert!(graph.get_subgraph(&new_subgraph_handle).unwrap().is_output_node_set());
+    }
+
+    #[test]
+    fn paste_nodes_into_subgraph() {
+        let mut project: HardeenProject<GeometryWorld> = HardeenProject::new(ProcessorRegistry::with_builtin_processors());
+
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        let translate = project.add_node("Translate").unwrap();
+        let instance = project.add_node("InstanceOnPoints").unwrap();
+        project.connect(&rectangle, &translate, 0).unwrap();
+
+        let clipboard = project.copy_nodes(&[rectangle, translate]).unwrap();
+
+        let subgraph_handle = project.get_current_graph().unwrap().get_subgraph_handle(&instance).unwrap();
+        project.go_level_down(subgraph_handle);
+
+        let mapping = project.paste_nodes(&clipboard).unwrap();
+        let subgraph = project.get_current_graph().unwrap();
+        let new_translate = subgraph.get_node_handle_by_name("Translate1").unwrap();
+        let new_rectangle = subgraph.get_node_handle_by_name("CreateRectangle1").unwrap();
+        assert!(mapping.values().any(|handle| handle == &new_rectangle));
+        assert!(subgraph.get_node(&new_translate).unwrap().get_all_input_handles() == vec![new_rectangle]);
+
+        project.undo().unwrap();
+        assert!(project.get_current_graph().unwrap().get_node_handle_by_name("Translate1").is_none());
+        assert!(project.get_current_graph().unwrap().get_node_handle_by_name("CreateRectangle1").is_none());
+    }
+}
@@ -0,0 +1,62 @@
+//! # Evaluation Context
+//!
+//! Graphs are evaluated at a point in time. The `EvaluationContext` carries the frame to evaluate
+//! and the frame rate of the project, and is handed to every processor and on into subgraphs.
+//!
+//! Most processors don't depend on time, so their outputs are cached once for all frames. Processors
+//! that read the context declare it with `BasicProcessor::is_time_dependent`, which makes their
+//! fingerprint, and with it the fingerprints of all nodes depending on them, differ per time.
+
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+
+pub const DEFAULT_FRAME_RATE: f64 = 24.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct EvaluationContext {
+    frame: f64,
+    frame_rate: f64,
+}
+
+impl EvaluationContext {
+    pub fn new(frame: f64, frame_rate: f64) -> Self {
+        EvaluationContext { frame, frame_rate }
+    }
+
+    /// The frame to evaluate. Fractional frames lie between two frames.
+    pub fn get_frame(&self) -> f64 {
+        self.frame
+    }
+
+    /// The number of frames per second.
+    pub fn get_frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    /// The time of the frame in seconds, with frame 0 at time 0.
+    pub fn get_time(&self) -> f64 {
+        if self.frame_rate > 0.0 {
+            self.frame / self.frame_rate
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the context of another frame at the same frame rate.
+    pub fn at_frame(&self, frame: f64) -> Self {
+        EvaluationContext::new(frame, self.frame_rate)
+    }
+}
+
+impl Default for EvaluationContext {
+    fn default() -> Self {
+        EvaluationContext::new(0.0, DEFAULT_FRAME_RATE)
+    }
+}
+
+impl Hash for EvaluationContext {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.frame.to_bits().hash(state);
+        self.frame_rate.to_bits().hash(state);
+    }
+}
@@ -0,0 +1,280 @@
+//! # Subgraph Definitions
+//!
+//! A `SubgraphDefinition` is a graph shared by several subgraph nodes, e.g. a generator that is used
+//! in many places. Each of these nodes refers to the definition by name and holds a copy of its
+//! graph with the very same handles, so an edit recorded for one copy applies to any other copy.
+//! `HardeenProject` keeps the definitions and brings all copies up to date whenever one of them is
+//! edited. Exposed parameters keep their values in each copy, so that every node can promote its
+//! own values.
+//!
+//! The definitions of a project form its asset library. Besides its graph, each definition declares
+//! the names of its inputs and carries a version. Its exposed parameters are the parameters of the
+//! nodes using it. Definitions are listed as node types next to the registered processors and can
+//! be exported into a `DefinitionFile`, to import them into other projects.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::vec::Vec;
+
+use super::*;
+
+/// A node using a definition, given by the subgraphs leading to its graph and its handle.
+pub type DefinitionUser<T> = (Vec<SubgraphHandle<T>>, NodeHandle<T>);
+
+pub struct SubgraphDefinition<T: Serialize> {
+    processor_type: String,
+    version: u32,
+    inputs: Vec<String>,
+    graph: Graph<T>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SubgraphDefinitionDocument {
+    pub processor: String,
+    #[serde(default = "default_definition_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    pub graph: GraphDocument,
+}
+
+fn default_definition_version() -> u32 {
+    1
+}
+
+/// Describes a definition the way `ProcessorTypeInfo` describes a registered processor.
+#[derive(Serialize)]
+pub struct DefinitionTypeInfo {
+    pub name: String,
+    pub processor_type: String,
+    pub version: u32,
+    pub input_type: ProcessorInputType,
+    pub inputs: Vec<String>,
+    pub parameters: Vec<PromotedParameter>,
+}
+
+/// A node type a project offers, which is either a registered processor or a definition.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum NodeTypeInfo<'a> {
+    Processor(&'a ProcessorTypeInfo),
+    Definition(DefinitionTypeInfo),
+}
+
+/// A single definition stored on its own, to share it between projects.
+#[derive(Serialize, Deserialize)]
+pub struct DefinitionFile {
+    pub version: u32,
+    pub name: String,
+    pub definition: SubgraphDefinitionDocument,
+}
+
+impl DefinitionFile {
+    pub fn new(name: &str, definition: SubgraphDefinitionDocument) -> Self {
+        DefinitionFile {
+            version: PROJECT_FILE_VERSION,
+            name: String::from(name),
+            definition,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, HardeenError> {
+        serde_json::to_string(self).map_err(|_error| HardeenError::ProjectFileInvalid)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, HardeenError> {
+        let header: ProjectFileHeader =
+            serde_json::from_str(json).map_err(|_error| HardeenError::ProjectFileInvalid)?;
+
+        if header.version > PROJECT_FILE_VERSION {
+            return Err(HardeenError::ProjectFileVersionUnsupported);
+        }
+
+        serde_json::from_str(json).map_err(|_error| HardeenError::ProjectFileInvalid)
+    }
+}
+
+impl<T: Serialize> SubgraphDefinition<T> {
+    /// Creates a definition at version 1. Its inputs are named after the graph input nodes of
+    /// `graph`, ordered by their index.
+    pub fn new(processor_type: &str, graph: Graph<T>) -> Self {
+        let mut graph_inputs: Vec<(usize, String)> = graph
+            .nodes
+            .get_iterator()
+            .filter_map(|node| node.get_graph_input_index().map(|index| (index, String::from(node.get_name()))))
+            .collect();
+        graph_inputs.sort();
+
+        let number_inputs = graph_inputs.last().map_or(0, |(index, _)| index + 1);
+        let inputs = (0..number_inputs)
+            .map(|index| match graph_inputs.iter().find(|(input_index, _)| *input_index == index) {
+                Some((_, name)) => name.clone(),
+                None => format!("Input{}", index + 1),
+            })
+            .collect();
+
+        SubgraphDefinition {
+            processor_type: String::from(processor_type),
+            version: default_definition_version(),
+            inputs,
+            graph,
+        }
+    }
+
+    /// The type of the subgraph processor that nodes using the definition are created with.
+    pub fn get_processor_type(&self) -> &str {
+        &self.processor_type
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// The names of the inputs nodes using the definition provide to graph input nodes.
+    pub fn get_inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    pub fn set_inputs(&mut self, inputs: Vec<String>) {
+        self.inputs = inputs;
+    }
+
+    pub fn get_graph(&self) -> &Graph<T> {
+        &self.graph
+    }
+
+    pub fn get_graph_mut(&mut self) -> &mut Graph<T> {
+        &mut self.graph
+    }
+
+    pub fn set_graph(&mut self, graph: Graph<T>) {
+        self.graph = graph;
+    }
+
+    pub fn to_document(&self) -> SubgraphDefinitionDocument {
+        SubgraphDefinitionDocument {
+            processor: self.processor_type.clone(),
+            version: self.version,
+            inputs: self.inputs.clone(),
+            graph: self.graph.to_document(),
+        }
+    }
+
+    pub fn from_document(document: &SubgraphDefinitionDocument, registry: &ProcessorRegistry<T>) -> Result<Self, HardeenError> {
+        let graph = Graph::from_document(&document.graph, &|processor_type: &str| registry.create_processor(processor_type))?;
+
+        Ok(SubgraphDefinition {
+            processor_type: document.processor.clone(),
+            version: document.version,
+            inputs: document.inputs.clone(),
+            graph,
+        })
+    }
+
+    pub fn get_type_info(&self, name: &str) -> DefinitionTypeInfo {
+        DefinitionTypeInfo {
+            name: String::from(name),
+            processor_type: self.processor_type.clone(),
+            version: self.version,
+            input_type: ProcessorInputType::Slotted {
+                number_of_slots: self.inputs.len() as u8,
+            },
+            inputs: self.inputs.clone(),
+            parameters: self.graph.get_exposed_parameters(),
+        }
+    }
+}
+
+impl<T: Serialize> Graph<T> {
+    /// Creates an independent copy of the graph with the same handles.
+    pub fn duplicate(&self, registry: &ProcessorRegistry<T>) -> Result<Graph<T>, HardeenError> {
+        Graph::from_document(&self.to_document(), &|processor_type: &str| registry.create_processor(processor_type))
+    }
+
+    /// Returns the names of all definitions used by nodes of this graph and its subgraphs.
+    pub fn get_used_definitions(&self) -> BTreeSet<String> {
+        let mut definitions = BTreeSet::new();
+
+        for node in self.nodes.get_iterator() {
+            if let Some(definition) = node.get_definition() {
+                definitions.insert(String::from(definition));
+            }
+        }
+
+        for subgraph in self.subgraphs.get_iterator() {
+            definitions.extend(subgraph.get_used_definitions());
+        }
+
+        definitions
+    }
+
+    /// Replaces the subgraph of every node using the definition `name`, within this graph and its
+    /// subgraphs, by a copy of `definition`. The subgraph at `source`, given relative to this graph,
+    /// is where the definition was taken from and is left as it is. Returns the updated nodes.
+    pub fn update_definition_users(
+        &mut self,
+        name: &str,
+        definition: &Graph<T>,
+        source: &[SubgraphHandle<T>],
+        registry: &ProcessorRegistry<T>,
+    ) -> Result<Vec<DefinitionUser<T>>, HardeenError> {
+        let mut users = Vec::new();
+        self.update_definition_users_at(&mut Vec::new(), name, definition, source, registry, &mut users)?;
+
+        Ok(users)
+    }
+
+    fn update_definition_users_at(
+        &mut self,
+        path: &mut Vec<SubgraphHandle<T>>,
+        name: &str,
+        definition: &Graph<T>,
+        source: &[SubgraphHandle<T>],
+        registry: &ProcessorRegistry<T>,
+        users: &mut Vec<DefinitionUser<T>>,
+    ) -> Result<(), HardeenError> {
+        let node_handles: Vec<NodeHandle<T>> = self.nodes.get_handle_iterator().collect();
+
+        for node_handle in node_handles {
+            let node = self.get_node(&node_handle)?;
+            let subgraph_handle = match node.get_processor_component() {
+                ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => subgraph_handle.clone(),
+                ProcessorComponent::BasicProcessor(_) => continue,
+            };
+            let is_user = node.get_definition() == Some(name);
+
+            path.push(subgraph_handle.clone());
+
+            if !is_user {
+                self.get_subgraph_mut(&subgraph_handle)?
+                    .update_definition_users_at(path, name, definition, source, registry, users)?;
+            } else if path.as_slice() != source {
+                let mut copy = definition.duplicate(registry)?;
+                copy.keep_exposed_parameter_values(self.get_subgraph(&subgraph_handle)?);
+                *self.get_subgraph_mut(&subgraph_handle)? = copy;
+
+                users.push((path[..path.len() - 1].to_vec(), node_handle));
+            }
+
+            path.pop();
+        }
+
+        Ok(())
+    }
+
+    /// Takes over the values of the exposed parameters `previous` shares with this graph. Values
+    /// that don't fit the parameter they are exposed from anymore are dropped.
+    fn keep_exposed_parameter_values(&mut self, previous: &Graph<T>) {
+        let exposed_names: Vec<String> = self.exposed_parameters.keys().cloned().collect();
+
+        for exposed_name in exposed_names {
+            if let Ok(value) = previous.get_exposed_parameter(&exposed_name) {
+                let _ = self.set_exposed_parameter(&exposed_name, value);
+            }
+        }
+    }
+}
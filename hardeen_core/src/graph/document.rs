@@ -0,0 +1,356 @@
+//! # Graph Documents
+//!
+//! A `GraphDocument` is the persistable counterpart of a `Graph`. Processors are stored by their type
+//! name together with their parameter values, so reading a document back requires a function that
+//! creates processors by name. Nodes and subgraphs are stored slot by slot, including free slots, so
+//! that handles referring to them are still valid after a round trip.
+//!
+//! A `ProjectFile` wraps the document of a root graph together with the version of the file format
+//! and the subgraph definitions of the project, as well as its frame rate and current frame.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::vec::Vec;
+
+use super::*;
+use crate::handled_vec::Handle;
+
+/// Version 2 added the bypass and mute flags of nodes. Files of version 1 are still read, with the
+/// defaults for the fields they lack.
+pub const PROJECT_FILE_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HandleDocument {
+    pub index: usize,
+    pub generation: usize,
+}
+
+impl HandleDocument {
+    pub fn from_handle<H: Handle>(handle: &H) -> Self {
+        HandleDocument {
+            index: handle.get_index(),
+            generation: handle.get_generation(),
+        }
+    }
+
+    pub fn to_handle<H: Handle>(self) -> H {
+        H::new(self.index, self.generation)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SlotDocument<D> {
+    pub generation: usize,
+    pub entry: Option<D>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NodeDocument {
+    #[serde(default)]
+    pub name: String,
+    pub processor: String,
+    pub parameters: BTreeMap<String, String>,
+    pub inputs: Vec<Option<HandleDocument>>,
+    pub subgraph: Option<HandleDocument>,
+    #[serde(default)]
+    pub bypassed: bool,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub definition: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExposedParameterDocument {
+    pub node: HandleDocument,
+    pub parameter: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GraphDocument {
+    pub nodes: Vec<SlotDocument<NodeDocument>>,
+    pub subgraphs: Vec<SlotDocument<GraphDocument>>,
+    pub output_node: Option<HandleDocument>,
+    pub exposed_parameters: BTreeMap<String, ExposedParameterDocument>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ProjectFileHeader {
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectFile {
+    pub version: u32,
+    pub graph: GraphDocument,
+    #[serde(default)]
+    pub definitions: BTreeMap<String, SubgraphDefinitionDocument>,
+    #[serde(default = "default_frame_rate")]
+    pub frame_rate: f64,
+    #[serde(default)]
+    pub frame: f64,
+}
+
+fn default_frame_rate() -> f64 {
+    DEFAULT_FRAME_RATE
+}
+
+impl ProjectFile {
+    pub fn new(graph: GraphDocument) -> Self {
+        ProjectFile {
+            version: PROJECT_FILE_VERSION,
+            graph,
+            definitions: BTreeMap::new(),
+            frame_rate: DEFAULT_FRAME_RATE,
+            frame: 0.0,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, HardeenError> {
+        serde_json::to_string(self).map_err(|_error| HardeenError::ProjectFileInvalid)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, HardeenError> {
+        let header: ProjectFileHeader =
+            serde_json::from_str(json).map_err(|_error| HardeenError::ProjectFileInvalid)?;
+
+        if header.version > PROJECT_FILE_VERSION {
+            return Err(HardeenError::ProjectFileVersionUnsupported);
+        }
+
+        serde_json::from_str(json).map_err(|_error| HardeenError::ProjectFileInvalid)
+    }
+}
+
+impl<T: Serialize> Node<T> {
+    pub(super) fn to_document(&self) -> NodeDocument {
+        let mut parameters = BTreeMap::new();
+
+        for parameter in self.get_parameters().iter() {
+            if let Ok(value) = self.get_parameter(parameter.param_name) {
+                parameters.insert(String::from(parameter.param_name), value.to_string());
+            }
+        }
+
+        let inputs = match self.get_input_component() {
+            InputComponent::Slotted(slotted_input) => slotted_input
+                .get_slots()
+                .iter()
+                .map(|slot| slot.as_ref().map(HandleDocument::from_handle))
+                .collect(),
+            InputComponent::Multiple(multiple_input) => multiple_input
+                .get_all_input_handles()
+                .iter()
+                .map(|handle| Some(HandleDocument::from_handle(handle)))
+                .collect(),
+        };
+
+        let subgraph = match self.get_processor_component() {
+            ProcessorComponent::BasicProcessor(_) => None,
+            ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => {
+                Some(HandleDocument::from_handle(subgraph_handle))
+            }
+        };
+
+        NodeDocument {
+            name: String::from(self.get_name()),
+            processor: String::from(self.get_processor_name()),
+            parameters,
+            inputs,
+            subgraph,
+            bypassed: self.is_bypassed(),
+            muted: self.is_muted(),
+            metadata: self.get_metadata().clone(),
+            definition: self.get_definition().map(String::from),
+        }
+    }
+}
+
+impl<T: Serialize> Graph<T> {
+    pub fn save_to_json(&self) -> Result<String, HardeenError> {
+        ProjectFile::new(self.to_document()).to_json()
+    }
+
+    pub fn load_from_json(json: &str, registry: &ProcessorRegistry<T>) -> Result<Graph<T>, HardeenError> {
+        let project_file = ProjectFile::from_json(json)?;
+
+        Graph::from_document(&project_file.graph, &|processor_type: &str| registry.create_processor(processor_type))
+    }
+
+    pub fn to_document(&self) -> GraphDocument {
+        let mut nodes = Vec::new();
+
+        for index in 0..self.nodes.get_length() {
+            if let Some(handle) = self.nodes.get_handle_for_index(index) {
+                nodes.push(SlotDocument {
+                    generation: handle.get_generation(),
+                    entry: self.nodes.get(&handle).ok().map(|node| node.to_document()),
+                });
+            }
+        }
+
+        let mut subgraphs = Vec::new();
+
+        for index in 0..self.subgraphs.get_length() {
+            if let Some(handle) = self.subgraphs.get_handle_for_index(index) {
+                subgraphs.push(SlotDocument {
+                    generation: handle.get_generation(),
+                    entry: self.subgraphs.get(&handle).ok().map(|subgraph| subgraph.to_document()),
+                });
+            }
+        }
+
+        let exposed_parameters = self
+            .exposed_parameters
+            .iter()
+            .map(|(name, exposed_parameter)| {
+                (
+                    name.clone(),
+                    ExposedParameterDocument {
+                        node: HandleDocument::from_handle(exposed_parameter.get_node_handle()),
+                        parameter: String::from(exposed_parameter.get_parameter_name()),
+                    },
+                )
+            })
+            .collect();
+
+        GraphDocument {
+            nodes,
+            subgraphs,
+            output_node: self.output_node_handle.as_ref().map(HandleDocument::from_handle),
+            exposed_parameters,
+        }
+    }
+
+    /// Rebuilds a graph from its document. `create_processor` is used to instantiate the processor
+    /// of each node from its type name.
+    pub fn from_document<F>(document: &GraphDocument, create_processor: &F) -> Result<Graph<T>, HardeenError>
+    where
+        F: Fn(&str) -> Result<BoxedProcessor<T>, HardeenError>,
+    {
+        let mut graph = Graph::new();
+
+        let mut subgraph_slots = Vec::new();
+        for slot in document.subgraphs.iter() {
+            let subgraph = match &slot.entry {
+                Some(subgraph_document) => Some(Graph::from_document(subgraph_document, create_processor)?),
+                None => None,
+            };
+            subgraph_slots.push((slot.generation, subgraph));
+        }
+        graph.subgraphs = HandledVec::from_slots(subgraph_slots);
+
+        let mut node_slots = Vec::new();
+        for slot in document.nodes.iter() {
+            let node = match &slot.entry {
+                Some(node_document) => Some(graph.node_from_document(node_document, create_processor)?),
+                None => None,
+            };
+            node_slots.push((slot.generation, node));
+        }
+        graph.nodes = HandledVec::from_slots(node_slots);
+        graph.rebuild_node_names();
+
+        for (index, slot) in document.nodes.iter().enumerate() {
+            if let Some(node_document) = &slot.entry {
+                let to: NodeHandle<T> = NodeHandle::new(index, slot.generation);
+
+                for (slot_number, input) in node_document.inputs.iter().enumerate() {
+                    if let Some(input) = input {
+                        let from: NodeHandle<T> = input.to_handle();
+
+                        if graph.get_node(&from).is_err() {
+                            return Err(HardeenError::ProjectFileInvalid);
+                        }
+
+                        graph.connect_to_slot(&from, &to, slot_number)?;
+                    }
+                }
+            }
+        }
+
+        for (name, exposed_parameter) in document.exposed_parameters.iter() {
+            let node_handle: NodeHandle<T> = exposed_parameter.node.to_handle();
+
+            if !graph.get_node(&node_handle)?.is_parameter(&exposed_parameter.parameter) {
+                return Err(HardeenError::ProjectFileInvalid);
+            }
+
+            graph.exposed_parameters.insert(
+                name.clone(),
+                ExposedParameter::new(&node_handle, &exposed_parameter.parameter),
+            );
+        }
+
+        if let Some(output_node) = document.output_node {
+            let output_node_handle: NodeHandle<T> = output_node.to_handle();
+
+            if graph.get_node(&output_node_handle).is_err() {
+                return Err(HardeenError::ProjectFileInvalid);
+            }
+
+            graph.output_node_handle = Some(output_node_handle);
+        }
+
+        Ok(graph)
+    }
+
+    pub(super) fn node_from_document<F>(&self, document: &NodeDocument, create_processor: &F) -> Result<Node<T>, HardeenError>
+    where
+        F: Fn(&str) -> Result<BoxedProcessor<T>, HardeenError>,
+    {
+        let mut node = match (create_processor(&document.processor)?, document.subgraph) {
+            (BoxedProcessor::Basic(processor), None) => Node::new_basic_processor_node(processor),
+            (BoxedProcessor::Subgraph(processor), Some(subgraph)) => {
+                let subgraph_handle: SubgraphHandle<T> = subgraph.to_handle();
+                self.subgraphs.get(&subgraph_handle)?;
+
+                Node::new_subgraph_processor_node(processor, subgraph_handle)
+            }
+            _ => return Err(HardeenError::ProjectFileInvalid),
+        };
+
+        for (parameter_name, value) in document.parameters.iter() {
+            let declaration = node.get_parameter_declaration(parameter_name)?;
+            let value = declaration.clamp(Parameter::from_string(value, declaration.param_type)?);
+            node.set_parameter(parameter_name, value)?;
+        }
+
+        node.set_name(document.name.clone());
+        node.set_bypassed(document.bypassed);
+        node.set_muted(document.muted);
+        node.set_definition(document.definition.clone());
+
+        for (key, value) in document.metadata.iter() {
+            node.set_metadata_value(key, value.clone());
+        }
+
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::GeometryWorld;
+
+    #[test]
+    fn load_version_1_files() {
+        let json = r#"{"version":1,"graph":{"nodes":[
+            {"generation":1,"entry":{"processor":"CreateRectangle","parameters":{"height":"5","position":"0,0","width":"12"},"inputs":[],"subgraph":null}},
+            {"generation":1,"entry":{"processor":"Scale","parameters":{"factor_x":"2","factor_y":"2"},"inputs":[{"index":0,"generation":1}],"subgraph":null}}
+        ],"subgraphs":[],"output_node":{"index":1,"generation":1},"exposed_parameters":{}}}"#;
+
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let graph: Graph<GeometryWorld> = Graph::load_from_json(json, &registry).unwrap();
+
+        let scale = graph.get_output_node_handle().unwrap();
+        assert!(!graph.get_node(&scale).unwrap().is_bypassed());
+        assert!(!graph.get_node(&scale).unwrap().is_muted());
+        assert_eq!(graph.process_graph_output(true).unwrap().get_bounding_rect().1.0, 12.0);
+
+        assert!(graph.save_to_json().unwrap().starts_with(&format!("{{\"version\":{}", PROJECT_FILE_VERSION)));
+    }
+}
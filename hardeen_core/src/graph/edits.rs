@@ -0,0 +1,368 @@
+//! # Graph Edits
+//!
+//! A `GraphEdit` is a single, reversible change to a graph. Applying an edit returns the edit that
+//! reverts it, which is what undo and redo in `HardeenProject` are built upon. Removed nodes are kept
+//! alive inside the reverting edit, so that they can be put back with their original handle.
+
+use serde::Serialize;
+use std::vec::Vec;
+
+use super::*;
+
+/// Connections of a node, each given by the node on the other end and the slot of the receiving node.
+pub(super) type SlotConnections<T> = Vec<(NodeHandle<T>, usize)>;
+
+/// A node that has been taken out of its graph, together with the connections it had, the subgraph
+/// it owned, the parameters of it the graph exposed and whether it was the output node.
+pub struct RemovedNode<T: Serialize> {
+    node: Node<T>,
+    subgraph: Option<Graph<T>>,
+    inputs: SlotConnections<T>,
+    outputs: SlotConnections<T>,
+    exposed_parameters: Vec<(String, String)>,
+    was_output_node: bool,
+}
+
+pub enum GraphEdit<T: Serialize> {
+    RemoveNode {
+        node_handle: NodeHandle<T>,
+    },
+    RestoreNode {
+        node_handle: NodeHandle<T>,
+        removed_node: Box<RemovedNode<T>>,
+    },
+    Connect {
+        from: NodeHandle<T>,
+        to: NodeHandle<T>,
+        slot: usize,
+    },
+    Disconnect {
+        from: NodeHandle<T>,
+        to: NodeHandle<T>,
+        slot: usize,
+    },
+    SetParameter {
+        node_handle: NodeHandle<T>,
+        parameter_name: String,
+        value: Parameter,
+    },
+    SetOutputNode {
+        node_handle: Option<NodeHandle<T>>,
+    },
+    SetNodeFlags {
+        node_handle: NodeHandle<T>,
+        bypassed: bool,
+        muted: bool,
+    },
+    RenameNode {
+        node_handle: NodeHandle<T>,
+        name: String,
+    },
+    /// Sets a metadata value of a node, or removes it if `value` is None.
+    SetMetadata {
+        node_handle: NodeHandle<T>,
+        key: String,
+        value: Option<serde_json::Value>,
+    },
+    ExposeParameter {
+        exposed_name: String,
+        parameter: Option<(NodeHandle<T>, String)>,
+    },
+    SetDefinition {
+        node_handle: NodeHandle<T>,
+        definition: Option<String>,
+    },
+}
+
+impl<T: Serialize> GraphEdit<T> {
+    /// Returns the nodes whose output the edit changes directly. These and the nodes downstream of
+    /// them become dirty. Returns None if the edit doesn't change any output of the graph.
+    pub fn get_changed_nodes(&self, graph: &Graph<T>) -> Option<Vec<NodeHandle<T>>> {
+        match self {
+            GraphEdit::RemoveNode { node_handle } => {
+                Some(graph.get_node(node_handle).map(|node| node.get_all_outputs().into_iter().collect()).unwrap_or_default())
+            }
+            GraphEdit::RestoreNode { node_handle, .. }
+            | GraphEdit::SetParameter { node_handle, .. }
+            | GraphEdit::SetNodeFlags { node_handle, .. } => Some(vec![node_handle.clone()]),
+            GraphEdit::Connect { to, .. } | GraphEdit::Disconnect { to, .. } => Some(vec![to.clone()]),
+            GraphEdit::SetOutputNode { .. } => Some(Vec::new()),
+            GraphEdit::RenameNode { .. }
+            | GraphEdit::SetMetadata { .. }
+            | GraphEdit::ExposeParameter { .. }
+            | GraphEdit::SetDefinition { .. } => None,
+        }
+    }
+
+    /// Applies the edit to `graph` and returns the edit that reverts it.
+    pub fn apply(self, graph: &mut Graph<T>) -> Result<GraphEdit<T>, HardeenError> {
+        match self {
+            GraphEdit::RemoveNode { node_handle } => {
+                let removed_node = Box::new(graph.take_node(&node_handle)?);
+
+                Ok(GraphEdit::RestoreNode {
+                    node_handle,
+                    removed_node,
+                })
+            }
+            GraphEdit::RestoreNode {
+                node_handle,
+                removed_node,
+            } => {
+                graph.restore_node(&node_handle, *removed_node)?;
+
+                Ok(GraphEdit::RemoveNode { node_handle })
+            }
+            GraphEdit::Connect { from, to, slot } => {
+                graph.connect_to_slot(&from, &to, slot)?;
+
+                Ok(GraphEdit::Disconnect { from, to, slot })
+            }
+            GraphEdit::Disconnect { from, to, slot } => {
+                match graph.get_node(&to)?.get_input_component() {
+                    InputComponent::Slotted(_) => graph.disconnect_from_slot(&from, &to, slot)?,
+                    InputComponent::Multiple(_) => graph.disconnect(&from, &to)?,
+                }
+
+                Ok(GraphEdit::Connect { from, to, slot })
+            }
+            GraphEdit::SetParameter {
+                node_handle,
+                parameter_name,
+                value,
+            } => {
+                let previous_value = graph.get_node_parameter(&node_handle, &parameter_name)?;
+                graph.set_node_parameter(&node_handle, &parameter_name, value)?;
+
+                Ok(GraphEdit::SetParameter {
+                    node_handle,
+                    parameter_name,
+                    value: previous_value,
+                })
+            }
+            GraphEdit::SetOutputNode { node_handle } => {
+                let previous_node_handle = graph.get_output_node_handle();
+
+                match node_handle {
+                    Some(node_handle) => {
+                        graph.set_output_node_handle(node_handle)?;
+                    }
+                    None => graph.output_node_handle = None,
+                }
+
+                Ok(GraphEdit::SetOutputNode {
+                    node_handle: previous_node_handle,
+                })
+            }
+            GraphEdit::SetNodeFlags {
+                node_handle,
+                bypassed,
+                muted,
+            } => {
+                let node = graph.get_node_mut(&node_handle)?;
+                let previous_bypassed = node.is_bypassed();
+                let previous_muted = node.is_muted();
+                node.set_bypassed(bypassed);
+                node.set_muted(muted);
+
+                Ok(GraphEdit::SetNodeFlags {
+                    node_handle,
+                    bypassed: previous_bypassed,
+                    muted: previous_muted,
+                })
+            }
+            GraphEdit::RenameNode { node_handle, name } => {
+                let previous_name = String::from(graph.get_node(&node_handle)?.get_name());
+                graph.set_node_name(&node_handle, &name)?;
+
+                Ok(GraphEdit::RenameNode {
+                    node_handle,
+                    name: previous_name,
+                })
+            }
+            GraphEdit::SetMetadata { node_handle, key, value } => {
+                let node = graph.get_node_mut(&node_handle)?;
+                let previous_value = match value {
+                    Some(value) => {
+                        let previous_value = node.get_metadata_value(&key).cloned();
+                        node.set_metadata_value(&key, value);
+                        previous_value
+                    }
+                    None => node.remove_metadata_value(&key),
+                };
+
+                Ok(GraphEdit::SetMetadata {
+                    node_handle,
+                    key,
+                    value: previous_value,
+                })
+            }
+            GraphEdit::ExposeParameter {
+                exposed_name,
+                parameter,
+            } => {
+                let previous_parameter = graph.exposed_parameters.get(&exposed_name).map(|exposed_parameter| {
+                    (
+                        exposed_parameter.get_node_handle().clone(),
+                        String::from(exposed_parameter.get_parameter_name()),
+                    )
+                });
+
+                match &parameter {
+                    Some((node_handle, parameter_name)) => {
+                        if !graph.get_node(node_handle)?.is_parameter(parameter_name) {
+                            return Err(HardeenError::NodeParameterDoesNotExist);
+                        }
+                        graph.expose_parameter(&exposed_name, node_handle, parameter_name)?;
+                    }
+                    None => {
+                        graph.exposed_parameters.remove(&exposed_name);
+                    }
+                }
+
+                Ok(GraphEdit::ExposeParameter {
+                    exposed_name,
+                    parameter: previous_parameter,
+                })
+            }
+            GraphEdit::SetDefinition {
+                node_handle,
+                definition,
+            } => {
+                graph.get_subgraph_handle(&node_handle)?;
+                let node = graph.get_node_mut(&node_handle)?;
+                let previous_definition = node.get_definition().map(String::from);
+                node.set_definition(definition);
+
+                Ok(GraphEdit::SetDefinition {
+                    node_handle,
+                    definition: previous_definition,
+                })
+            }
+        }
+    }
+}
+
+impl<T: Serialize> Graph<T> {
+    /// Returns the nodes connected to the inputs of a node and the nodes its output is connected
+    /// to, each with the slot of the receiving node.
+    pub(super) fn get_node_connections(&self, handle: &NodeHandle<T>) -> Result<(SlotConnections<T>, SlotConnections<T>), HardeenError> {
+        let node = self.get_node(handle)?;
+
+        let inputs = match node.get_input_component() {
+            InputComponent::Slotted(slotted_input) => slotted_input
+                .get_slots()
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, input)| input.as_ref().map(|input| (input.clone(), slot)))
+                .collect(),
+            InputComponent::Multiple(multiple_input) => multiple_input
+                .get_all_input_handles()
+                .into_iter()
+                .map(|input| (input, 0))
+                .collect(),
+        };
+
+        let mut outputs = Vec::new();
+
+        for output_handle in node.get_all_outputs().into_iter() {
+            match self.get_node(&output_handle)?.get_input_component() {
+                InputComponent::Slotted(slotted_input) => {
+                    for (slot, input) in slotted_input.get_slots().iter().enumerate() {
+                        if input.as_ref() == Some(handle) {
+                            outputs.push((output_handle.clone(), slot));
+                        }
+                    }
+                }
+                InputComponent::Multiple(_) => outputs.push((output_handle.clone(), 0)),
+            }
+        }
+
+        Ok((inputs, outputs))
+    }
+
+    /// Removes a node from the graph and returns it together with its connections. The subgraph of
+    /// a subgraph processor node is taken out along with it, and with it any subgraphs it holds.
+    /// Parameters of the node the graph exposes are no longer exposed, and if the node was the output
+    /// node the graph has no output anymore.
+    pub fn take_node(&mut self, handle: &NodeHandle<T>) -> Result<RemovedNode<T>, HardeenError> {
+        let (inputs, outputs) = self.get_node_connections(handle)?;
+
+        let mut exposed_parameters: Vec<(String, String)> = self
+            .exposed_parameters
+            .iter()
+            .filter(|(_, exposed_parameter)| exposed_parameter.get_node_handle() == handle)
+            .map(|(exposed_name, exposed_parameter)| (exposed_name.clone(), String::from(exposed_parameter.get_parameter_name())))
+            .collect();
+        exposed_parameters.sort();
+
+        for (exposed_name, _) in exposed_parameters.iter() {
+            self.exposed_parameters.remove(exposed_name);
+        }
+
+        let was_output_node = self.output_node_handle.as_ref() == Some(handle);
+
+        if was_output_node {
+            self.output_node_handle = None;
+        }
+
+        self.disconnect_all_nodes(handle)?;
+        let mut node = self.nodes.take_entry(handle)?;
+        self.node_names.remove(node.get_name());
+        node.invalidate_cache();
+
+        let subgraph = match node.get_processor_component() {
+            ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => Some(self.subgraphs.take_entry(subgraph_handle)?),
+            ProcessorComponent::BasicProcessor(_) => None,
+        };
+
+        Ok(RemovedNode {
+            node,
+            subgraph,
+            inputs,
+            outputs,
+            exposed_parameters,
+            was_output_node,
+        })
+    }
+
+    /// Puts a node that has been taken out by `take_node` back into the graph at its old handle and
+    /// restores its connections.
+    pub fn restore_node(&mut self, handle: &NodeHandle<T>, removed_node: RemovedNode<T>) -> Result<(), HardeenError> {
+        let mut node = removed_node.node;
+
+        // Another node may have taken the name in the meantime.
+        if self.node_names.contains_key(node.get_name()) {
+            let name = self.get_unique_node_name(node.get_processor_name());
+            node.set_name(name);
+        }
+
+        if let (ProcessorComponent::SubgraphProcessor(_, subgraph_handle), Some(subgraph)) =
+            (node.get_processor_component(), removed_node.subgraph)
+        {
+            self.subgraphs.restore_entry(subgraph_handle, subgraph)?;
+        }
+
+        let name = String::from(node.get_name());
+        self.nodes.restore_entry(handle, node)?;
+        self.node_names.insert(name, handle.clone());
+
+        for (from, slot) in removed_node.inputs.iter() {
+            self.connect_to_slot(from, handle, *slot)?;
+        }
+
+        for (to, slot) in removed_node.outputs.iter() {
+            self.connect_to_slot(handle, to, *slot)?;
+        }
+
+        for (exposed_name, parameter_name) in removed_node.exposed_parameters.iter() {
+            self.expose_parameter(exposed_name, handle, parameter_name)?;
+        }
+
+        if removed_node.was_output_node {
+            self.set_output_node_handle(handle.clone())?;
+        }
+
+        Ok(())
+    }
+}
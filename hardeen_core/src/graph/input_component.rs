@@ -18,6 +18,7 @@ use crate::hardeen_error::HardeenError;
 pub struct SlottedInput<H: Handle + Clone + PartialEq> {
     input_handles: Vec<Option<H>>,
     slots_taken: usize,
+    optional_slots: bool,
 }
 
 impl<H: Handle + Clone + PartialEq> SlottedInput<H> {
@@ -25,27 +26,46 @@ impl<H: Handle + Clone + PartialEq> SlottedInput<H> {
         SlottedInput {
             input_handles: vec![None; number_of_slots as usize],
             slots_taken: 0,
+            optional_slots: false,
         }
     }
 
-    pub fn connect_input(&mut self, handle: &H, slot_number: usize) -> Result<(), HardeenError> {
-        if slot_number as usize > self.input_handles.len() {
-            return Err(HardeenError::InvalidInputSlotNumber);
+    /// Creates slots that may stay empty, as long as the occupied slots come first. This way the
+    /// inputs of a node are always in slot order without gaps.
+    pub fn with_optional_slots(number_of_slots: u8) -> Self {
+        SlottedInput {
+            optional_slots: true,
+            ..SlottedInput::new(number_of_slots)
+        }
+    }
+
+    /// Checks whether `slot_number` exists and is free.
+    pub fn can_connect_input(&self, slot_number: usize) -> Result<(), HardeenError> {
+        match self.input_handles.get(slot_number) {
+            None => Err(HardeenError::InvalidInputSlotNumber),
+            Some(Some(_)) => Err(HardeenError::InputSlotOccupied),
+            Some(None) => Ok(()),
         }
+    }
+
+    pub fn connect_input(&mut self, handle: &H, slot_number: usize) -> Result<(), HardeenError> {
+        self.can_connect_input(slot_number)?;
 
-        self.input_handles[slot_number as usize] = Some(handle.clone());
+        self.input_handles[slot_number] = Some(handle.clone());
         self.slots_taken += 1;
 
         Ok(())
     }
 
     pub fn disconnect_input(&mut self, slot_number: usize) -> Result<(), HardeenError> {
-        if slot_number as usize > self.input_handles.len() {
-            return Err(HardeenError::InvalidInputSlotNumber);
-        }
+        let slot = self
+            .input_handles
+            .get_mut(slot_number)
+            .ok_or(HardeenError::InvalidInputSlotNumber)?;
 
-        self.input_handles[slot_number as usize] = None;
-        self.slots_taken -= 1;
+        if slot.take().is_some() {
+            self.slots_taken -= 1;
+        }
 
         Ok(())
     }
@@ -55,6 +75,7 @@ impl<H: Handle + Clone + PartialEq> SlottedInput<H> {
             if let Some(input_handle) = input_handle_slot {
                 if input_handle == handle {
                     *input_handle_slot = None;
+                    self.slots_taken -= 1;
                 }
             }
         }
@@ -63,23 +84,29 @@ impl<H: Handle + Clone + PartialEq> SlottedInput<H> {
     }
 
     pub fn is_slot_occupied(&self, slot_number: usize) -> Result<bool, HardeenError> {
-        if slot_number as usize > self.input_handles.len() {
-            return Err(HardeenError::InvalidInputSlotNumber);
+        match self.input_handles.get(slot_number) {
+            None => Err(HardeenError::InvalidInputSlotNumber),
+            Some(slot) => Ok(slot.is_some()),
         }
-
-        Ok(self.input_handles[slot_number as usize] != None)
     }
 
     pub fn is_input_satisfied(&self) -> bool {
+        if self.optional_slots {
+            return self.input_handles[self.slots_taken..].iter().all(|slot| slot.is_none());
+        }
+
         self.slots_taken as usize == self.input_handles.len()
     }
 
     pub fn get_input(&self, slot_number: usize) -> Result<Option<H>, HardeenError> {
-        if slot_number as usize > self.input_handles.len() {
-            return Err(HardeenError::InvalidInputSlotNumber);
-        }
+        self.input_handles
+            .get(slot_number)
+            .cloned()
+            .ok_or(HardeenError::InvalidInputSlotNumber)
+    }
 
-        Ok(self.input_handles[slot_number as usize].clone())
+    pub fn get_slots(&self) -> &[Option<H>] {
+        &self.input_handles
     }
 
     pub fn get_all_input_handles(&self) -> Vec<H> {
@@ -131,3 +158,48 @@ pub enum InputComponent<H: Handle + Hash + PartialEq + Eq + Clone> {
     Slotted(SlottedInput<H>),
     Multiple(MultipleInput<H>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handled_vec::MarkedHandle;
+
+    #[test]
+    fn slot_bounds_and_occupation() {
+        let mut input: SlottedInput<MarkedHandle<u8>> = SlottedInput::new(2);
+        let first = MarkedHandle::new(0, 0);
+        let second = MarkedHandle::new(1, 0);
+
+        assert!(matches!(input.connect_input(&first, 2), Err(HardeenError::InvalidInputSlotNumber)));
+        assert!(matches!(input.disconnect_input(2), Err(HardeenError::InvalidInputSlotNumber)));
+        assert!(matches!(input.get_input(2), Err(HardeenError::InvalidInputSlotNumber)));
+
+        input.connect_input(&first, 0).unwrap();
+        assert!(matches!(input.connect_input(&second, 0), Err(HardeenError::InputSlotOccupied)));
+        assert!(!input.is_input_satisfied());
+
+        input.connect_input(&second, 1).unwrap();
+        assert!(input.is_input_satisfied());
+
+        input.disconnect_input(1).unwrap();
+        input.disconnect_input(1).unwrap();
+        assert!(!input.is_input_satisfied());
+
+        input.connect_input(&second, 1).unwrap();
+        assert!(input.is_input_satisfied());
+    }
+    #[test]
+    fn optional_slots_are_filled_in_order() {
+        let mut input: SlottedInput<MarkedHandle<u8>> = SlottedInput::with_optional_slots(3);
+        let first = MarkedHandle::new(0, 0);
+        let second = MarkedHandle::new(1, 0);
+
+        assert!(input.is_input_satisfied());
+
+        input.connect_input(&second, 1).unwrap();
+        assert!(!input.is_input_satisfied());
+
+        input.connect_input(&first, 0).unwrap();
+        assert!(input.is_input_satisfied());
+    }
+}
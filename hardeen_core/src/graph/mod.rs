@@ -5,23 +5,45 @@
 //! 
 //! 
 
+use itertools::Itertools;
 use serde::Serialize;
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::collections::{HashMap, HashSet};
 use std::vec::Vec;
 
+mod clipboard;
+mod context;
+mod definition;
+mod document;
+mod edits;
 mod input_component;
 mod nodes;
+mod output_cache;
 mod parameters;
 mod processor_component;
-
+mod profile;
+mod registry;
+mod subnet;
+mod validation;
+
+pub use clipboard::*;
+pub use context::*;
+pub use definition::*;
+pub use document::*;
+pub use edits::*;
 pub use nodes::*;
+pub use output_cache::*;
 pub use parameters::*;
 pub use processor_component::*;
+pub use profile::{MeasureOutput, NodeProfile, OutputSize, Profile};
 pub use input_component::*;
+pub use registry::*;
+pub use subnet::*;
+pub use validation::*;
 
-use crate::hardeen_error::HardeenError;
-use crate::handled_vec::{HandledVec, HandledVecError, MarkedHandle, StdVec};
+use crate::hardeen_error::{HardeenError, NodeError};
+use crate::shared::{map_parallel, Shared, SharedCell, ThreadSafe};
+use profile::{ProfileRecorder, Stopwatch};
+use crate::handled_vec::{Handle, HandledVec, HandledVecError, MarkedHandle, StdVec};
 
 pub type NodeHandle<T> = MarkedHandle<Node<T>>;
 pub type SubgraphHandle<T> = MarkedHandle<Graph<T>>;
@@ -52,6 +74,13 @@ impl<T: Serialize> ExposedParameter<T> {
     }
 }
 
+/// A parameter exposed by a subgraph, as it appears on the node owning the subgraph.
+#[derive(Serialize, Clone)]
+pub struct PromotedParameter {
+    pub name: String,
+    pub declaration: ProcessorParameter,
+}
+
 #[derive(Serialize)]
 #[serde(tag = "type")]
 pub enum ProcessorInputType {
@@ -74,16 +103,28 @@ impl ProcessorTypeInfo {
             parameters
         }
     }
+
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get_parameters(&self) -> &[ProcessorParameter] {
+        &self.parameters
+    }
 }
 
 #[derive(Serialize)]
 pub struct Graph<T: Serialize> {
-    input: Vec<Option<T>>,
     nodes: HandledVec<NodeHandle<T>, StdVec<Node<T>>>,
     exposed_parameters: HashMap<String, ExposedParameter<T>>,
     subgraphs: HandledVec<SubgraphHandle<T>, StdVec<Graph<T>>>,
     output_node_handle: Option<NodeHandle<T>>,
-    processor_types: Vec<ProcessorTypeInfo>
+    #[serde(skip)]
+    node_names: HashMap<String, NodeHandle<T>>,
+    #[serde(skip)]
+    name_counters: HashMap<String, usize>,
+    #[serde(skip)]
+    profile_recorder: SharedCell<Option<ProfileRecorder<T>>>,
 }
 
 impl std::convert::From<HandledVecError> for HardeenError {
@@ -95,21 +136,44 @@ impl std::convert::From<HandledVecError> for HardeenError {
 impl<T: Serialize> Graph<T> {
     pub fn new() -> Graph<T> {
         Graph {
-            input: Vec::new(),
             nodes: HandledVec::new(),
             exposed_parameters: HashMap::new(),
             subgraphs: HandledVec::new(),
             output_node_handle: None,
-            processor_types: Vec::new()
+            node_names: HashMap::new(),
+            name_counters: HashMap::new(),
+            profile_recorder: SharedCell::new(None),
         }
     }
 
-    pub fn get_processor_types(&self) -> &[ProcessorTypeInfo] {
-        &self.processor_types
+    pub fn add_processor_node_by_type(
+        &mut self,
+        registry: &ProcessorRegistry<T>,
+        processor_type: &str,
+    ) -> Result<NodeHandle<T>, HardeenError> {
+        Ok(self.add_boxed_processor_node(registry.create_processor(processor_type)?))
+    }
+
+    pub fn add_boxed_processor_node(&mut self, processor: BoxedProcessor<T>) -> NodeHandle<T> {
+        match processor {
+            BoxedProcessor::Basic(processor) => self.add_processor_node(processor),
+            BoxedProcessor::Subgraph(processor) => self.add_subgraph_processor_node(processor),
+        }
     }
 
     pub fn add_processor_node(&mut self, processor: Box<dyn BasicProcessor<T>>) -> NodeHandle<T> {
-        self.nodes.add_entry(Node::new_basic_processor_node(processor))
+        self.add_node(Node::new_basic_processor_node(processor))
+    }
+
+    /// Adds a node under a unique name made from its processor name and a counter.
+    fn add_node(&mut self, mut node: Node<T>) -> NodeHandle<T> {
+        let name = self.get_unique_node_name(node.get_processor_name());
+        node.set_name(name.clone());
+
+        let node_handle = self.nodes.add_entry(node);
+        self.node_names.insert(name, node_handle.clone());
+
+        node_handle
     }
 
     pub fn add_subgraph_processor_node(
@@ -120,8 +184,7 @@ impl<T: Serialize> Graph<T> {
         let subgraph = Graph::new();
         let subgraph_handle = self.subgraphs.add_entry(subgraph);
 
-        self.nodes
-            .add_entry(Node::new_subgraph_processor_node(processor, subgraph_handle))
+        self.add_node(Node::new_subgraph_processor_node(processor, subgraph_handle))
     }
 
     pub fn get_subgraph_handle(&self, node_handle: &NodeHandle<T>) -> Result<SubgraphHandle<T>, HardeenError> {
@@ -157,7 +220,7 @@ impl<T: Serialize> Graph<T> {
     pub fn set_exposed_parameter(
         &mut self,
         name: &str,
-        value: &str
+        value: Parameter
     ) -> Result<(), HardeenError> {
         match self.exposed_parameters.get(name) {
             Some(exposed_parameter) => {
@@ -189,10 +252,96 @@ impl<T: Serialize> Graph<T> {
         Ok(())
     }
 
+    pub fn get_exposed_parameter(&self, name: &str) -> Result<Parameter, HardeenError> {
+        match self.exposed_parameters.get(name) {
+            Some(exposed_parameter) => self
+                .get_node(exposed_parameter.get_node_handle())?
+                .get_parameter(exposed_parameter.get_parameter_name()),
+            None => Err(HardeenError::ExposedParameterDoesNotExist),
+        }
+    }
+
+    /// Returns the parameters exposed by the subgraph of a node, sorted by name. They are read and
+    /// set like the node's own parameters through `get_node_parameter` and `set_node_parameter`,
+    /// except for those whose name is taken by a parameter of the node's processor.
+    pub fn get_promoted_parameters(&self, node_handle: &NodeHandle<T>) -> Result<Vec<PromotedParameter>, HardeenError> {
+        let node = self.get_node(node_handle)?;
+
+        let subgraph = match node.get_processor_component() {
+            ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => self.get_subgraph(subgraph_handle)?,
+            ProcessorComponent::BasicProcessor(_) => return Ok(Vec::new()),
+        };
+
+        Ok(subgraph
+            .get_exposed_parameters()
+            .into_iter()
+            .filter(|promoted_parameter| !node.is_parameter(&promoted_parameter.name))
+            .collect())
+    }
+
+    /// Returns the parameters this graph exposes together with their declarations, sorted by name.
+    pub fn get_exposed_parameters(&self) -> Vec<PromotedParameter> {
+        let mut exposed_parameters: Vec<PromotedParameter> = self
+            .exposed_parameters
+            .iter()
+            .filter_map(|(name, exposed_parameter)| {
+                let declaration = self
+                    .get_node(exposed_parameter.get_node_handle())
+                    .and_then(|exposed_node| exposed_node.get_parameter_declaration(exposed_parameter.get_parameter_name()))
+                    .ok()?;
+
+                Some(PromotedParameter {
+                    name: name.clone(),
+                    declaration: declaration.clone(),
+                })
+            })
+            .collect();
+
+        exposed_parameters.sort_by(|a, b| a.name.cmp(&b.name));
+
+        exposed_parameters
+    }
+
+    /// Returns a parameter of a node or, if the node has no parameter of that name, the parameter
+    /// its subgraph exposes under that name.
+    pub fn get_node_parameter(&self, node_handle: &NodeHandle<T>, parameter_name: &str) -> Result<Parameter, HardeenError> {
+        let node = self.get_node(node_handle)?;
+
+        match node.get_processor_component() {
+            ProcessorComponent::SubgraphProcessor(_, subgraph_handle) if !node.is_parameter(parameter_name) => self
+                .get_subgraph(subgraph_handle)?
+                .get_exposed_parameter(parameter_name)
+                .map_err(|_| HardeenError::NodeParameterDoesNotExist),
+            _ => node.get_parameter(parameter_name),
+        }
+    }
+
+    /// Sets a parameter like `get_node_parameter` reads it. Changing a parameter inside a subgraph
+    /// changes the fingerprint of the subgraph node, so its cached output is not used anymore.
+    pub fn set_node_parameter(&mut self, node_handle: &NodeHandle<T>, parameter_name: &str, value: Parameter) -> Result<(), HardeenError> {
+        let node = self.get_node(node_handle)?;
+
+        match node.get_processor_component() {
+            ProcessorComponent::SubgraphProcessor(_, subgraph_handle) if !node.is_parameter(parameter_name) => {
+                let subgraph_handle = subgraph_handle.clone();
+                self.get_subgraph_mut(&subgraph_handle)?
+                    .set_exposed_parameter(parameter_name, value)
+                    .map_err(|error| match error {
+                        HardeenError::ExposedParameterDoesNotExist => HardeenError::NodeParameterDoesNotExist,
+                        error => error,
+                    })
+            }
+            _ => self.get_node_mut(node_handle)?.set_parameter(parameter_name, value),
+        }
+    }
+
+    pub fn set_node_parameter_from_str(&mut self, node_handle: &NodeHandle<T>, parameter_name: &str, value: &str) -> Result<(), HardeenError> {
+        let parameter_type = self.get_node_parameter(node_handle, parameter_name)?.get_type();
+        self.set_node_parameter(node_handle, parameter_name, Parameter::from_string(value, parameter_type)?)
+    }
+
     pub fn remove_node(&mut self, handle: NodeHandle<T>) -> Result<(), HardeenError> {
-        self.nodes.is_handle_valid(&handle)?;
-        self.disconnect_all_nodes(&handle)?;
-        self.nodes.remove_entry(handle)?;
+        self.take_node(&handle)?;
         Ok(())
     }
 
@@ -204,30 +353,139 @@ impl<T: Serialize> Graph<T> {
         Ok(self.nodes.get_mut(handle)?)
     }
 
-    pub fn connect_to_slot(
-        &mut self,
+    pub fn get_node_handle_by_name(&self, name: &str) -> Option<NodeHandle<T>> {
+        self.node_names.get(name).cloned()
+    }
+
+    pub fn get_node_names(&self) -> Vec<String> {
+        self.node_names.keys().cloned().collect()
+    }
+
+    /// Renames a node. Names must be unique within the graph, must not be empty and must not
+    /// contain slashes, which separate the names in node paths.
+    pub fn set_node_name(&mut self, handle: &NodeHandle<T>, name: &str) -> Result<(), HardeenError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(HardeenError::NodeNameInvalid { name: String::from(name) });
+        }
+
+        match self.node_names.get(name) {
+            Some(named_handle) if named_handle == handle => return Ok(()),
+            Some(_) => return Err(HardeenError::NodeNameTaken { name: String::from(name) }),
+            None => {}
+        }
+
+        let node = self.nodes.get_mut(handle)?;
+        self.node_names.remove(node.get_name());
+        node.set_name(String::from(name));
+        self.node_names.insert(String::from(name), handle.clone());
+
+        Ok(())
+    }
+
+    /// Resolves a path of slash separated node names, like `instance/rectangle`, where all but the
+    /// last name refer to subgraph nodes. Returns the handles of the subgraphs leading to the graph
+    /// containing the node, together with the node's handle.
+    pub fn resolve_node_path(&self, path: &str) -> Result<(Vec<SubgraphHandle<T>>, NodeHandle<T>), HardeenError> {
+        let path_invalid = || HardeenError::NodePathInvalid { path: String::from(path) };
+
+        let mut names = path.trim_matches('/').split('/').peekable();
+        let mut subgraph_path = Vec::new();
+        let mut graph = self;
+
+        while let Some(name) = names.next() {
+            let node_handle = graph.get_node_handle_by_name(name).ok_or_else(path_invalid)?;
+
+            if names.peek().is_none() {
+                return Ok((subgraph_path, node_handle));
+            }
+
+            let subgraph_handle = graph.get_subgraph_handle(&node_handle).map_err(|_| path_invalid())?;
+            graph = graph.get_subgraph(&subgraph_handle)?;
+            subgraph_path.push(subgraph_handle);
+        }
+
+        Err(path_invalid())
+    }
+
+    /// Returns the node at a path as accepted by `resolve_node_path`.
+    pub fn get_node_by_path(&self, path: &str) -> Result<&Node<T>, HardeenError> {
+        let (subgraph_path, node_handle) = self.resolve_node_path(path)?;
+
+        let mut graph = self;
+        for subgraph_handle in subgraph_path.iter() {
+            graph = graph.get_subgraph(subgraph_handle)?;
+        }
+
+        graph.get_node(&node_handle)
+    }
+
+    fn get_unique_node_name(&mut self, base_name: &str) -> String {
+        let counter = self.name_counters.entry(String::from(base_name)).or_insert(0);
+
+        loop {
+            *counter += 1;
+            let name = format!("{}{}", base_name, counter);
+
+            if !self.node_names.contains_key(&name) {
+                return name;
+            }
+        }
+    }
+
+    /// Registers the names of all nodes again. Missing and duplicate names are replaced by unique
+    /// ones, which is needed for documents written before nodes had names.
+    fn rebuild_node_names(&mut self) {
+        self.node_names.clear();
+
+        let handles: Vec<NodeHandle<T>> = self.nodes.get_handle_iterator().collect();
+
+        for handle in handles.iter() {
+            let (name, processor_name) = match self.nodes.get(handle) {
+                Ok(node) => (String::from(node.get_name()), String::from(node.get_processor_name())),
+                Err(_) => continue,
+            };
+
+            let name = if name.is_empty() || name.contains('/') || self.node_names.contains_key(&name) {
+                self.get_unique_node_name(&processor_name)
+            } else {
+                name
+            };
+
+            if let Ok(node) = self.nodes.get_mut(handle) {
+                node.set_name(name.clone());
+            }
+            self.node_names.insert(name, handle.clone());
+        }
+    }
+
+    /// Checks whether `from` can be connected to `slot` of `to` and returns the error
+    /// `connect_to_slot` would fail with otherwise. The graph is not changed.
+    pub fn can_connect(
+        &self,
         from: &NodeHandle<T>,
         to: &NodeHandle<T>,
         slot: usize,
     ) -> Result<(), HardeenError> {
-        if !self.is_handle_valid(from) || !self.is_handle_valid(to) {
-            panic!("NodeHandle is invalid!");
-        }
+        self.get_node(from)?;
+        self.get_node(to)?.can_connect_input_node(slot)?;
 
         if self.path_exists(to, from) {
-            panic!("Circles are not allowed!");
+            return Err(HardeenError::ConnectionCreatesCycle);
         }
 
-        match self
-            .nodes
-            .get_mut(to)
-            .unwrap()
-            .connect_input_node(from, slot)
-            .and(self.nodes.get_mut(from).unwrap().connect_output_node(to))
-        {
-            Ok(()) => Ok(()),
-            Err(_error) => Err(HardeenError::InvalidHandle),
-        }
+        Ok(())
+    }
+
+    pub fn connect_to_slot(
+        &mut self,
+        from: &NodeHandle<T>,
+        to: &NodeHandle<T>,
+        slot: usize,
+    ) -> Result<(), HardeenError> {
+        self.can_connect(from, to, slot)?;
+
+        self.nodes.get_mut(to)?.connect_input_node(from, slot)?;
+        self.nodes.get_mut(from)?.connect_output_node(to)
     }
 
     pub fn connect(
@@ -239,30 +497,22 @@ impl<T: Serialize> Graph<T> {
     }
 
     pub fn disconnect_all_nodes(&mut self, handle: &NodeHandle<T>) -> Result<(), HardeenError> {
-        if let Err(_error) = self.nodes.get(handle) {
-            return Err(HardeenError::InvalidHandle);
-        }
-
-        let node = self.nodes.get(handle).unwrap();
-        let input_node_handles = node.get_all_input_handles();
+        let node = self.get_node(handle)?;
+        let input_node_handles: Vec<NodeHandle<T>> = node.get_all_input_handles().into_iter().unique().collect();
         let output_node_handles = node.get_all_outputs();
 
         for other_handle in output_node_handles.iter() {
-            match self.nodes.get_mut(&other_handle) {
-                Ok(other_node) => {
-                    other_node.disconnect_output_node(handle)?;
-                }
-                Err(_error) => {}
+            if let Ok(other_node) = self.nodes.get_mut(other_handle) {
+                other_node.disconnect_input_node(handle)?;
             }
+            self.nodes.get_mut(handle)?.disconnect_output_node(other_handle)?;
         }
 
-        for (slot_number, other_handle) in input_node_handles.iter().enumerate() {
-            match self.nodes.get_mut(&other_handle) {
-                Ok(other_node) => {
-                    other_node.disconnect_input_node_slotted(slot_number)?;
-                }
-                Err(_error) => {}
+        for other_handle in input_node_handles.iter() {
+            if let Ok(other_node) = self.nodes.get_mut(other_handle) {
+                other_node.disconnect_output_node(handle)?;
             }
+            self.nodes.get_mut(handle)?.disconnect_input_node(other_handle)?;
         }
 
         Ok(())
@@ -274,14 +524,25 @@ impl<T: Serialize> Graph<T> {
         to: &NodeHandle<T>,
         slot: usize,
     ) -> Result<(), HardeenError> {
-        let from_node = self.nodes.get_mut(from).unwrap();
+        self.get_node(from)?;
 
-        from_node.disconnect_output_node(to).and(
-            self.nodes
-                .get_mut(to)
-                .unwrap()
-                .disconnect_input_node_slotted(slot),
-        )
+        let to_node = self.nodes.get_mut(to)?;
+        match to_node.get_input_component() {
+            InputComponent::Slotted(slotted_input) => {
+                if slotted_input.get_input(slot)?.as_ref() != Some(from) {
+                    return Err(HardeenError::NodesNotConnected);
+                }
+            }
+            InputComponent::Multiple(_) => return Err(HardeenError::NodeInputTypeMismatch),
+        }
+        to_node.disconnect_input_node_slotted(slot)?;
+
+        // The same node may still be connected to another slot.
+        if !to_node.get_all_input_handles().contains(from) {
+            self.nodes.get_mut(from)?.disconnect_output_node(to)?;
+        }
+
+        Ok(())
     }
 
     pub fn disconnect(
@@ -289,82 +550,192 @@ impl<T: Serialize> Graph<T> {
         from: &NodeHandle<T>,
         to: &NodeHandle<T>,
     ) -> Result<(), HardeenError> {
-        let from_node = self.nodes.get_mut(from).unwrap();
+        if !self.get_node(to)?.get_all_input_handles().contains(from) {
+            return Err(HardeenError::NodesNotConnected);
+        }
 
-        from_node
-            .disconnect_output_node(to)
-            .and(self.nodes.get_mut(to).unwrap().disconnect_input_node(from))
+        self.nodes.get_mut(from)?.disconnect_output_node(to)?;
+        self.nodes.get_mut(to)?.disconnect_input_node(from)
     }
 
-    pub fn set_output_node_handle(&mut self, output_node_handle: NodeHandle<T>) {
+    pub fn set_output_node_handle(&mut self, output_node_handle: NodeHandle<T>) -> Result<(), HardeenError> {
         if !self.is_handle_valid(&output_node_handle) {
-            panic!("Node with this handle does not exist!");
-        }
-        if let ProcessorComponent::SubgraphProcessor(_,_) = self.get_node(&output_node_handle).unwrap().get_processor_component() {
-            self.invalidate_cache(&output_node_handle);
+            return Err(HardeenError::InvalidHandle);
         }
         self.output_node_handle = Some(output_node_handle);
+
+        Ok(())
     }
 
-    pub fn process_graph_output(&self, use_caches: bool) -> Result<Rc<T>, HardeenError> {
-        if let Some(output_node_handle) = self.output_node_handle.clone() {
-            return self.process_node(&output_node_handle, use_caches);
+    /// Returns the fingerprint of the graph's output in the given context, which changes whenever the
+    /// output would.
+    pub fn get_output_fingerprint(&self, context: &EvaluationContext) -> Result<u64, HardeenError> {
+        match &self.output_node_handle {
+            Some(output_node_handle) => {
+                let order = self.get_evaluation_order(output_node_handle)?;
+                Ok(self.get_fingerprints(&order, &GraphInputs::default(), context)?[output_node_handle])
+            }
+            None => Err(HardeenError::GraphOutputNotSet),
         }
+    }
 
-        Err(HardeenError::GraphOutputNotSet)
+    /// Fingerprints the nodes of an evaluation order, which must list inputs before their outputs.
+    fn get_fingerprints(
+        &self,
+        order: &[NodeHandle<T>],
+        graph_inputs: &GraphInputs<T>,
+        context: &EvaluationContext,
+    ) -> Result<HashMap<NodeHandle<T>, u64>, HardeenError> {
+        let mut fingerprints: HashMap<NodeHandle<T>, u64> = HashMap::new();
+
+        for handle in order.iter() {
+            let node = self.get_node(handle)?;
+
+            let input_fingerprints: Vec<u64> = node
+                .get_evaluated_input_handles()
+                .iter()
+                .filter_map(|input_handle| fingerprints.get(input_handle).copied())
+                .collect();
+
+            let subgraph_fingerprint = match node.get_processor_component() {
+                ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => {
+                    self.subgraphs.get(subgraph_handle)?.get_output_fingerprint(context).ok()
+                }
+                ProcessorComponent::BasicProcessor(_) => None,
+            };
+
+            fingerprints.insert(handle.clone(), node.get_fingerprint(&input_fingerprints, subgraph_fingerprint, graph_inputs, context));
+        }
+
+        Ok(fingerprints)
     }
 
-    fn process_node(&self, node_handle: &NodeHandle<T>, use_caches: bool) -> Result<Rc<T>, HardeenError> {
-        let mut inputs: Vec<Rc<T>> = Vec::new();
+    /// Returns `node_handle` and all nodes it depends on, each node after its inputs.
+    fn get_evaluation_order(&self, node_handle: &NodeHandle<T>) -> Result<Vec<NodeHandle<T>>, HardeenError> {
+        self.get_evaluation_order_of(std::slice::from_ref(node_handle))
+    }
 
-        let node = self.get_node(node_handle)?;
+    /// Returns the given nodes and all nodes they depend on, each node after its inputs.
+    fn get_evaluation_order_of(&self, node_handles: &[NodeHandle<T>]) -> Result<Vec<NodeHandle<T>>, HardeenError> {
+        let mut order = Vec::new();
+        let mut visited: HashSet<NodeHandle<T>> = HashSet::new();
+        let mut stack: Vec<(NodeHandle<T>, bool)> = node_handles.iter().rev().map(|handle| (handle.clone(), false)).collect();
 
-        if !node.is_input_satisfied() {
-            return Err(HardeenError::NodeInputNotSatisfied);
-        }
+        while let Some((handle, inputs_done)) = stack.pop() {
+            if inputs_done {
+                order.push(handle);
+                continue;
+            }
 
-        if use_caches {
-            if let Some(cached_output) = node.get_cached_output() {
-                return Ok(cached_output);
+            if !visited.insert(handle.clone()) {
+                continue;
+            }
+
+            let node = self.get_node(&handle)?;
+            stack.push((handle.clone(), true));
+
+            for input_handle in node.get_all_input_handles().into_iter().rev() {
+                if !visited.contains(&input_handle) {
+                    stack.push((input_handle, false));
+                }
             }
         }
 
-        for input_node_handle in node.get_all_input_handles().iter() {
-            if let Ok(result) = self.process_node(input_node_handle, use_caches) {
-                inputs.push(result);
-            } else {
-                return Err(HardeenError::ErrorProcessingNode);
+        Ok(order)
+    }
+
+    fn node_error(&self, node_handle: &NodeHandle<T>, node: &Node<T>, error: HardeenError) -> HardeenError {
+        HardeenError::ErrorProcessingNode(NodeError {
+            subgraph_path: Vec::new(),
+            node: node_handle.get(),
+            processor_name: String::from(node.get_processor_name()),
+            error: Box::new(error),
+        })
+    }
+
+    pub fn invalidate_cache(&mut self, node_handle: &NodeHandle<T>) {
+        let mut visited: HashSet<NodeHandle<T>> = HashSet::new();
+        let mut stack = vec![node_handle.clone()];
+
+        while let Some(handle) = stack.pop() {
+            if !visited.insert(handle.clone()) {
+                continue;
             }
+
+            let node = self
+                .get_node_mut(&handle)
+                .expect("Node to process does not exist!");
+            node.invalidate_cache();
+
+            stack.extend(node.get_all_outputs());
         }
+    }
 
-        let result = match node.get_processor_component() {
-            ProcessorComponent::BasicProcessor(processor) => {
-                (*processor).run(inputs)
-            },
-            ProcessorComponent::SubgraphProcessor(processor, subgraph_handle) => {
-                let subgraph = self.subgraphs.get(&subgraph_handle)?;
-                (*processor).run(inputs, subgraph)
+    /// Marks a node and all nodes downstream of it as dirty. Their cached outputs are kept, since
+    /// they are stored by fingerprint and can't be mistaken for the current output.
+    pub fn mark_dirty(&self, node_handle: &NodeHandle<T>) -> Result<(), HardeenError> {
+        let mut visited: HashSet<NodeHandle<T>> = HashSet::new();
+        let mut stack = vec![node_handle.clone()];
+
+        while let Some(handle) = stack.pop() {
+            if !visited.insert(handle.clone()) {
+                continue;
             }
-        };
 
-        node.set_cached_output(result.clone());
+            let node = self.get_node(&handle)?;
+            node.mark_dirty();
+
+            stack.extend(node.get_all_outputs());
+        }
+
+        Ok(())
+    }
 
-        Ok(result)
+    /// Returns the nodes whose subgraph is the given one.
+    pub fn get_subgraph_owners(&self, subgraph_handle: &SubgraphHandle<T>) -> Vec<NodeHandle<T>> {
+        self.nodes
+            .get_handle_iterator()
+            .filter(|node_handle| {
+                self.get_node(node_handle).is_ok_and(|node| match node.get_processor_component() {
+                    ProcessorComponent::SubgraphProcessor(_, handle) => handle == subgraph_handle,
+                    ProcessorComponent::BasicProcessor(_) => false,
+                })
+            })
+            .collect()
     }
 
-    pub fn invalidate_cache(&mut self, node_handle: &NodeHandle<T>) {
-        let node = self
-            .get_node_mut(node_handle)
-            .expect("Node to process does not exist!");
-        node.invalidate_cache();
+    /// Drops subgraphs no node refers to, here and within the remaining subgraphs, and returns how
+    /// many were dropped. Removing a node removes its subgraph, but graphs saved by earlier versions
+    /// may still hold orphaned ones.
+    pub fn collect_garbage(&mut self) -> Result<usize, HardeenError> {
+        let owned_subgraphs: HashSet<SubgraphHandle<T>> = self
+            .nodes
+            .get_iterator()
+            .filter_map(|node| match node.get_processor_component() {
+                ProcessorComponent::SubgraphProcessor(_, subgraph_handle) => Some(subgraph_handle.clone()),
+                ProcessorComponent::BasicProcessor(_) => None,
+            })
+            .collect();
+
+        let orphaned_subgraphs: Vec<SubgraphHandle<T>> = self
+            .subgraphs
+            .get_handle_iterator()
+            .filter(|subgraph_handle| !owned_subgraphs.contains(subgraph_handle))
+            .collect();
+
+        let mut dropped = orphaned_subgraphs.len();
+
+        for subgraph_handle in orphaned_subgraphs {
+            self.subgraphs.remove_entry(subgraph_handle)?;
+        }
 
-        let node = self
-            .get_node(node_handle)
-            .expect("Node to process does not exist!");
+        let subgraph_handles: Vec<SubgraphHandle<T>> = self.subgraphs.get_handle_iterator().collect();
 
-        for out_node_handle in node.get_all_outputs().iter() {
-            self.invalidate_cache(&out_node_handle);
+        for subgraph_handle in subgraph_handles.iter() {
+            dropped += self.subgraphs.get_mut(subgraph_handle)?.collect_garbage()?;
         }
+
+        Ok(dropped)
     }
 
     pub fn get_output_node(&self) -> Result<&Node<T>, HardeenError> {
@@ -386,30 +757,449 @@ impl<T: Serialize> Graph<T> {
     }
 
     fn path_exists(&self, from: &NodeHandle<T>, to: &NodeHandle<T>) -> bool {
-        if from == to {
-            return true;
+        let mut visited: HashSet<NodeHandle<T>> = HashSet::new();
+        let mut stack = vec![from.clone()];
+
+        while let Some(handle) = stack.pop() {
+            if &handle == to {
+                return true;
+            }
+
+            if !visited.insert(handle.clone()) {
+                continue;
+            }
+
+            let node = self.nodes.get(&handle).expect("Node does not exist!");
+            stack.extend(node.get_all_outputs());
+        }
+
+        false
+    }
+
+    fn is_handle_valid(&self, handle: &NodeHandle<T>) -> bool {
+        // Removed nodes keep the generation of their slot, so look the node itself up.
+        self.nodes.get(handle).is_ok()
+    }
+}
+
+/// Evaluation needs the processors and outputs to be shareable between threads when the `parallel`
+/// feature is enabled. Muted nodes output the default value.
+impl<T: Serialize + ThreadSafe + Default> Graph<T> {
+    /// Evaluates the graph at frame 0 of the default frame rate.
+    pub fn process_graph_output(&self, use_caches: bool) -> Result<Shared<T>, HardeenError> {
+        self.process_graph_output_in_context(&EvaluationContext::default(), use_caches)
+    }
+
+    /// Evaluates the graph at the frame of `context`.
+    pub fn process_graph_output_in_context(&self, context: &EvaluationContext, use_caches: bool) -> Result<Shared<T>, HardeenError> {
+        self.process_graph_output_with_inputs(&GraphInputs::default(), context, use_caches)
+    }
+
+    /// Evaluates the graph with the given graph inputs, which are output by its graph input nodes.
+    /// Subgraph processors use this to pass their own inputs on to their subgraph. As the graph input
+    /// nodes are fingerprinted by their inputs, the subgraph may be evaluated with caches.
+    pub fn process_graph_output_with_inputs(
+        &self,
+        graph_inputs: &GraphInputs<T>,
+        context: &EvaluationContext,
+        use_caches: bool,
+    ) -> Result<Shared<T>, HardeenError> {
+        if let Some(output_node_handle) = self.output_node_handle.clone() {
+            return self.process_node(&output_node_handle, graph_inputs, context, use_caches);
+        }
+
+        Err(HardeenError::GraphOutputNotSet)
+    }
+
+    /// Like `process_graph_output_in_context`, but also returns a profile of all nodes that were
+    /// needed for the output, including the nodes inside subgraphs. The profile is returned even if
+    /// the evaluation fails and then contains the nodes evaluated up to the failure.
+    pub fn process_graph_output_with_profile(&self, context: &EvaluationContext, use_caches: bool) -> (Result<Shared<T>, HardeenError>, Profile)
+    where
+        T: MeasureOutput,
+    {
+        self.start_profiling(T::measure_output);
+
+        let stopwatch = Stopwatch::start();
+        let result = self.process_graph_output_in_context(context, use_caches);
+
+        let mut profile = Profile {
+            wall_time_ms: stopwatch.elapsed_ms(),
+            nodes: Vec::new(),
+        };
+        self.finish_profiling(&[], &mut profile.nodes);
+
+        (result, profile)
+    }
+
+    fn start_profiling(&self, measure: fn(&T) -> OutputSize) {
+        self.profile_recorder.with(|recorder| *recorder = Some(ProfileRecorder::new(measure)));
+
+        for subgraph in self.subgraphs.get_iterator() {
+            subgraph.start_profiling(measure);
         }
+    }
 
-        let start = self.nodes.get(from).expect("Node does not exist!");
+    fn finish_profiling(&self, subgraph_path: &[(usize, usize)], nodes: &mut Vec<NodeProfile>) {
+        if let Some(recorder) = self.profile_recorder.with(|recorder| recorder.take()) {
+            nodes.extend(recorder.into_nodes().into_iter().map(|mut node| {
+                node.subgraph_path = subgraph_path.to_vec();
+                node
+            }));
+        }
 
-        let start_output_nodes = start.get_all_outputs();
+        for subgraph_handle in self.subgraphs.get_handle_iterator() {
+            if let Ok(subgraph) = self.subgraphs.get(&subgraph_handle) {
+                let mut path = subgraph_path.to_vec();
+                path.push(subgraph_handle.get());
+                subgraph.finish_profiling(&path, nodes);
+            }
+        }
+    }
 
-        if start_output_nodes.is_empty() {
-            false
-        } else {
-            for kv in start_output_nodes.iter() {
-                if self.path_exists(kv, to) {
-                    return true;
+    fn is_profiling(&self) -> bool {
+        self.profile_recorder.with(|recorder| recorder.is_some())
+    }
+
+    fn record_profile(&self, node_handle: &NodeHandle<T>, node: &Node<T>, wall_time_ms: f64, cache_hit: bool, output: &T) {
+        self.profile_recorder.with(|recorder| {
+            if let Some(recorder) = recorder {
+                recorder.record(node_handle.get(), node.get_processor_name(), wall_time_ms, cache_hit, output);
+            }
+        });
+    }
+
+    /// Evaluates a node. The nodes it depends on are put in topological order and fingerprinted.
+    /// With `use_caches`, outputs cached under the current fingerprint are reused and only the nodes
+    /// that are actually missing are run, each of them exactly once. Without `use_caches`, all of
+    /// them are run and the caches are left as they are.
+    fn process_node(
+        &self,
+        node_handle: &NodeHandle<T>,
+        graph_inputs: &GraphInputs<T>,
+        context: &EvaluationContext,
+        use_caches: bool,
+    ) -> Result<Shared<T>, HardeenError> {
+        let order = self.get_evaluation_order(node_handle)?;
+        let fingerprints = self.get_fingerprints(&order, graph_inputs, context)?;
+        let profiling = self.is_profiling();
+
+        let mut results: HashMap<NodeHandle<T>, Shared<T>> = HashMap::new();
+        let mut required: HashSet<NodeHandle<T>> = HashSet::new();
+        required.insert(node_handle.clone());
+
+        for handle in order.iter().rev() {
+            if !required.contains(handle) {
+                continue;
+            }
+
+            let node = self.get_node(handle)?;
+
+            if !node.is_input_satisfied() && !node.is_bypassed() && !node.is_muted() {
+                return Err(self.node_error(handle, node, HardeenError::NodeInputNotSatisfied));
+            }
+
+            let cached_output = if use_caches { node.get_cached_output(fingerprints[handle]) } else { None };
+
+            match cached_output {
+                Some(cached_output) => {
+                    if profiling {
+                        self.record_profile(handle, node, 0.0, true, &cached_output);
+                    }
+                    node.mark_clean();
+                    results.insert(handle.clone(), cached_output);
+                }
+                None => required.extend(node.get_evaluated_input_handles()),
+            }
+        }
+
+        // Nodes that only depend on nodes of earlier levels are independent of each other and may
+        // run concurrently.
+        let mut levels: HashMap<NodeHandle<T>, usize> = HashMap::new();
+        let mut batches: Vec<Vec<NodeHandle<T>>> = Vec::new();
+
+        for handle in order.iter() {
+            if !required.contains(handle) || results.contains_key(handle) {
+                continue;
+            }
+
+            let level = self
+                .get_node(handle)?
+                .get_evaluated_input_handles()
+                .iter()
+                .filter_map(|input_handle| levels.get(input_handle).map(|level| level + 1))
+                .max()
+                .unwrap_or(0);
+
+            levels.insert(handle.clone(), level);
+
+            if batches.len() <= level {
+                batches.push(Vec::new());
+            }
+            batches[level].push(handle.clone());
+        }
+
+        for batch in batches.into_iter() {
+            let jobs = batch
+                .into_iter()
+                .map(|handle| {
+                    let input_handles = self.get_node(&handle)?.get_evaluated_input_handles();
+
+                    let inputs = input_handles
+                        .iter()
+                        .map(|input_handle| results.get(input_handle).cloned().ok_or(HardeenError::InvalidHandle))
+                        .collect::<Result<Vec<Shared<T>>, HardeenError>>()?;
+                    let input_fingerprints = input_handles.iter().map(|input_handle| fingerprints[input_handle]).collect();
+
+                    Ok((handle, GraphInputs::new(inputs, input_fingerprints)))
+                })
+                .collect::<Result<Vec<(NodeHandle<T>, GraphInputs<T>)>, HardeenError>>()?;
+
+            let outputs = map_parallel(jobs, |(handle, inputs)| -> Result<(NodeHandle<T>, Shared<T>), HardeenError> {
+                let node = self.get_node(&handle)?;
+                let stopwatch = if profiling { Some(Stopwatch::start()) } else { None };
+                let result = self.run_node(&handle, node, inputs, graph_inputs, context)?;
+
+                if use_caches {
+                    node.set_cached_output(fingerprints[&handle], result.clone());
                 }
+                node.mark_clean();
+
+                if let Some(stopwatch) = stopwatch {
+                    self.record_profile(&handle, node, stopwatch.elapsed_ms(), false, &result);
+                }
+
+                Ok((handle, result))
+            });
+
+            for output in outputs.into_iter() {
+                let (handle, result) = output?;
+                results.insert(handle, result);
             }
-            false
         }
+
+        results.remove(node_handle).ok_or(HardeenError::InvalidHandle)
     }
 
-    fn is_handle_valid(&self, handle: &NodeHandle<T>) -> bool {
-        match self.nodes.is_handle_valid(handle) {
-            Ok(_true) => true,
-            Err(_error) => false,
+    fn run_node(
+        &self,
+        node_handle: &NodeHandle<T>,
+        node: &Node<T>,
+        inputs: GraphInputs<T>,
+        graph_inputs: &GraphInputs<T>,
+        context: &EvaluationContext,
+    ) -> Result<Shared<T>, HardeenError> {
+        if node.is_muted() {
+            return Ok(Shared::new(T::default()));
+        }
+
+        if node.is_bypassed() {
+            return Ok(inputs.into_values().into_iter().next().unwrap_or_else(|| Shared::new(T::default())));
+        }
+
+        let result = match node.get_processor_component() {
+            ProcessorComponent::BasicProcessor(processor) => match processor.get_graph_input_index() {
+                Some(index) => graph_inputs.get(index).cloned().ok_or(HardeenError::GraphInputNotConnected { index }),
+                None => (*processor).run(inputs.into_values(), context),
+            },
+            ProcessorComponent::SubgraphProcessor(processor, subgraph_handle) => {
+                let subgraph = self.subgraphs.get(&subgraph_handle)?;
+
+                match (*processor).run(inputs, subgraph, context) {
+                    Err(HardeenError::ErrorProcessingNode(mut node_error)) => {
+                        node_error.subgraph_path.insert(0, subgraph_handle.get());
+                        Err(HardeenError::ErrorProcessingNode(node_error))
+                    }
+                    result => result
+                }
+            }
+        };
+
+        match result {
+            Err(error @ HardeenError::ErrorProcessingNode(_)) => Err(error),
+            Err(error) => Err(self.node_error(node_handle, node, error)),
+            result => result
+        }
+    }
+}
+
+/// Adds a node of each of the built-in processor types to `graph` and returns their handles in order.
+#[cfg(test)]
+pub(crate) fn add_builtin_nodes<const N: usize>(
+    graph: &mut Graph<crate::geometry::GeometryWorld>,
+    processor_types: [&str; N],
+) -> [NodeHandle<crate::geometry::GeometryWorld>; N] {
+    let registry = ProcessorRegistry::with_builtin_processors();
+    processor_types.map(|processor_type| graph.add_processor_node_by_type(&registry, processor_type).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::GeometryWorld;
+    use crate::project::HardeenProject;
+
+    #[test]
+    fn connection_errors() {
+        let mut graph = Graph::new();
+        let [rectangle, scale, translate, removed] = add_builtin_nodes(&mut graph, ["CreateRectangle", "Scale", "Translate", "Translate"]);
+        graph.remove_node(removed.clone()).unwrap();
+
+        assert!(matches!(graph.connect(&removed, &scale), Err(HardeenError::InvalidHandle)));
+        assert!(matches!(graph.set_output_node_handle(removed.clone()), Err(HardeenError::InvalidHandle)));
+        assert!(matches!(graph.can_connect(&rectangle, &scale, 1), Err(HardeenError::InvalidInputSlotNumber)));
+
+        graph.connect(&rectangle, &scale).unwrap();
+        assert!(matches!(graph.can_connect(&translate, &scale, 0), Err(HardeenError::InputSlotOccupied)));
+
+        graph.connect(&scale, &translate).unwrap();
+        assert!(matches!(graph.connect(&translate, &rectangle), Err(HardeenError::ConnectionCreatesCycle)));
+        assert!(matches!(graph.connect(&rectangle, &rectangle), Err(HardeenError::ConnectionCreatesCycle)));
+
+        assert!(matches!(graph.disconnect_from_slot(&translate, &scale, 0), Err(HardeenError::NodesNotConnected)));
+        graph.disconnect_from_slot(&rectangle, &scale, 0).unwrap();
+        assert!(graph.can_connect(&rectangle, &scale, 0).is_ok());
+        assert!(!graph.get_node(&scale).unwrap().is_input_satisfied());
+        assert!(graph.get_node(&rectangle).unwrap().get_all_outputs().is_empty());
+    }
+
+    #[test]
+    fn node_names_and_paths() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph = Graph::new();
+        let [first, second, instance] = add_builtin_nodes(&mut graph, ["CreateRectangle", "CreateRectangle", "InstanceOnPoints"]);
+        assert_eq!(graph.get_node(&first).unwrap().get_name(), "CreateRectangle1");
+        assert_eq!(graph.get_node(&second).unwrap().get_name(), "CreateRectangle2");
+
+        assert!(matches!(graph.set_node_name(&second, "CreateRectangle1"), Err(HardeenError::NodeNameTaken { .. })));
+        assert!(matches!(graph.set_node_name(&second, "a/b"), Err(HardeenError::NodeNameInvalid { .. })));
+        graph.set_node_name(&second, "outline").unwrap();
+        assert!(graph.get_node_handle_by_name("outline") == Some(second.clone()));
+        assert!(graph.get_node_handle_by_name("CreateRectangle2").is_none());
+
+        graph.set_node_name(&instance, "instance").unwrap();
+        let subgraph_handle = graph.get_subgraph_handle(&instance).unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let [hair] = add_builtin_nodes(subgraph, ["AddPoints"]);
+        subgraph.set_node_name(&hair, "hair_curve").unwrap();
+
+        let (path, handle) = graph.resolve_node_path("instance/hair_curve").unwrap();
+        assert!(path == vec![subgraph_handle]);
+        assert!(handle == hair);
+        assert_eq!(graph.get_node_by_path("/instance/hair_curve").unwrap().get_processor_name(), "AddPoints");
+        assert!(matches!(graph.resolve_node_path("outline/hair_curve"), Err(HardeenError::NodePathInvalid { .. })));
+        assert!(matches!(graph.resolve_node_path("instance/missing"), Err(HardeenError::NodePathInvalid { .. })));
+
+        let mut loaded = Graph::load_from_json(&graph.save_to_json().unwrap(), &registry).unwrap();
+        assert_eq!(loaded.get_node_by_path("instance/hair_curve").unwrap().get_processor_name(), "AddPoints");
+        assert!(loaded.get_node_handle_by_name("outline") == Some(second.clone()));
+
+        let [third] = add_builtin_nodes(&mut loaded, ["CreateRectangle"]);
+        assert_eq!(loaded.get_node(&third).unwrap().get_name(), "CreateRectangle2");
+    }
+
+    #[test]
+    fn subgraphs_read_graph_inputs() {
+        let mut graph = Graph::new();
+        let [empty, points, instance] = add_builtin_nodes(&mut graph, ["Empty", "AddPoints", "InstanceOnPoints"]);
+        graph.get_node_mut(&points).unwrap().set_parameter_from_str("positions", "0,0;10,0;").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let [graph_input] = add_builtin_nodes(subgraph, ["GraphInput"]);
+        subgraph.set_output_node_handle(graph_input.clone()).unwrap();
+
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 4);
+
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        subgraph.get_node_mut(&graph_input).unwrap().set_parameter("index", Parameter::UnsignedInteger(1)).unwrap();
+
+        match graph.process_graph_output(false) {
+            Err(HardeenError::ErrorProcessingNode(node_error)) => {
+                assert_eq!(node_error.node, graph_input.get());
+                assert!(matches!(*node_error.error, HardeenError::GraphInputNotConnected { index: 1 }));
+            },
+            _ => panic!("Expected GraphInputNotConnected!")
+        }
+    }
+
+    #[test]
+    fn subnets_cache_by_graph_inputs() {
+        let mut graph = Graph::new();
+        let [rectangle, subnet] = add_builtin_nodes(&mut graph, ["CreateRectangle", "Subnet"]);
+        graph.connect(&rectangle, &subnet).unwrap();
+        graph.set_output_node_handle(subnet.clone()).unwrap();
+
+        let subgraph_handle = graph.get_subgraph_handle(&subnet).unwrap();
+        let subgraph = graph.get_subgraph_for_node(&subnet).unwrap();
+        let [graph_input, marker, merge] = add_builtin_nodes(subgraph, ["GraphInput", "CreateRectangle", "Merge"]);
+        subgraph.get_node_mut(&marker).unwrap().set_parameter_from_str("width", "1").unwrap();
+        subgraph.connect(&graph_input, &merge).unwrap();
+        subgraph.connect(&marker, &merge).unwrap();
+        subgraph.set_output_node_handle(merge.clone()).unwrap();
+
+        let context = EvaluationContext::default();
+        let (result, _) = graph.process_graph_output_with_profile(&context, true);
+        assert_eq!(result.unwrap().get_bounding_rect().1.0, 5.0);
+
+        graph.get_node_mut(&rectangle).unwrap().set_parameter_from_str("width", "30").unwrap();
+        let (result, profile) = graph.process_graph_output_with_profile(&context, true);
+        assert_eq!(result.unwrap().get_bounding_rect().1.0, 15.0);
+        assert!(!profile.get_node_profile(&[subgraph_handle.get()], graph_input.get()).unwrap().cache_hit);
+        assert!(profile.get_node_profile(&[subgraph_handle.get()], marker.get()).unwrap().cache_hit);
+
+        graph.get_node_mut(&rectangle).unwrap().set_parameter_from_str("width", "10").unwrap();
+        let (result, profile) = graph.process_graph_output_with_profile(&context, true);
+        assert_eq!(result.unwrap().get_bounding_rect().1.0, 5.0);
+        assert!(profile.get_node_profile(&[], subnet.get()).unwrap().cache_hit);
+
+        let subgraph = graph.get_subgraph(&subgraph_handle).unwrap();
+        assert!(subgraph.process_graph_output(true).is_err());
+    }
+
+    #[test]
+    fn promote_exposed_parameters() {
+        let mut project: HardeenProject<GeometryWorld> = HardeenProject::new(ProcessorRegistry::with_builtin_processors());
+
+        let empty = project.add_node("Empty").unwrap();
+        let instance = project.add_node("InstanceOnPoints").unwrap();
+        project.connect(&empty, &instance, 0).unwrap();
+        project.set_output_node(&instance).unwrap();
+
+        let graph = project.get_current_graph_mut().unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let [rectangle] = add_builtin_nodes(subgraph, ["CreateRectangle"]);
+        subgraph.set_output_node_handle(rectangle.clone()).unwrap();
+        subgraph.expose_parameter("width", &rectangle, "width").unwrap();
+        subgraph.expose_parameter("group_name", &rectangle, "height").unwrap();
+
+        let graph = project.get_current_graph().unwrap();
+        let promoted = graph.get_promoted_parameters(&instance).unwrap();
+        assert_eq!(promoted.len(), 1);
+        assert_eq!(promoted[0].name, "width");
+        assert_eq!(promoted[0].declaration.param_type, ParameterType::Float);
+        assert_eq!(graph.get_node_parameter(&instance, "width").unwrap(), Parameter::Float(10.0));
+        assert_eq!(graph.get_node_parameter(&instance, "group_name").unwrap(), Parameter::String(String::from("all")));
+        let output = graph.process_graph_output(true).unwrap();
+
+        project.set_parameter(&instance, "width", Parameter::Float(4.0)).unwrap();
+        let graph = project.get_current_graph().unwrap();
+        assert_eq!(graph.get_node_parameter(&instance, "width").unwrap(), Parameter::Float(4.0));
+        assert!(!Shared::ptr_eq(&output, &graph.process_graph_output(true).unwrap()));
+
+        project.undo().unwrap();
+        let graph = project.get_current_graph_mut().unwrap();
+        assert_eq!(graph.get_node_parameter(&instance, "width").unwrap(), Parameter::Float(10.0));
+
+        graph.set_node_parameter_from_str(&instance, "width", "6").unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        assert_eq!(subgraph.get_node(&rectangle).unwrap().get_parameter("width").unwrap(), Parameter::Float(6.0));
+
+        match graph.set_node_parameter(&instance, "height", Parameter::Float(1.0)) {
+            Err(HardeenError::NodeParameterDoesNotExist) => {},
+            _ => panic!("Expected NodeParameterDoesNotExist!")
         }
     }
 }
@@ -4,35 +4,45 @@
 //! It has 3 main jobs:
 //!     - Relay get/set properties of its ProcessorComponent
 //!     - Store information about incoming and outgoing nodes
-//!     - Cache the result of its ProcessorComponent
+//!     - Cache the results of its ProcessorComponent, keyed by fingerprint
 //! Note that it does not directly invoke the RunComponent. This is done by the `Graph` the node is
 //! part of.
 
 use serde::Serialize;
-use std::collections::{HashSet};
-use std::rc::Rc;
+use std::collections::{BTreeMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::vec::Vec;
-use std::cell::RefCell;
 
+use super::context::EvaluationContext;
 use super::parameters::*;
 
 use super::input_component::*;
 use super::processor_component::*;
+use super::output_cache::*;
 use super::SubgraphHandle;
 
-use crate::handled_vec::MarkedHandle;
+use crate::handled_vec::{Handle, MarkedHandle};
 
 use crate::hardeen_error::HardeenError;
+use crate::shared::{Shared, SharedCell};
 
 pub type NodeHandle<T> = MarkedHandle<Node<T>>;
 
 #[derive(Serialize)]
 pub struct Node<T: Serialize> {
+    name: String,
     processor_component: ProcessorComponent<T>,
     input_component: InputComponent<NodeHandle<T>>,
     output_nodes: HashSet<NodeHandle<T>>,
+    bypassed: bool,
+    muted: bool,
+    metadata: BTreeMap<String, serde_json::Value>,
+    definition: Option<String>,
     #[serde(skip)]
-    cached_output: RefCell<Option<Rc<T>>>,
+    cached_output: SharedCell<OutputCache<T>>,
+    #[serde(skip)]
+    dirty: SharedCell<bool>,
 }
 
 impl<T: Serialize> Node<T> {
@@ -58,11 +68,22 @@ impl<T: Serialize> Node<T> {
         run_component: ProcessorComponent<T>,
         input_component: InputComponent<NodeHandle<T>>
     ) -> Self {
+        let name = match &run_component {
+            ProcessorComponent::BasicProcessor(processor) => processor.get_processor_name(),
+            ProcessorComponent::SubgraphProcessor(processor, _) => processor.get_processor_name(),
+        };
+
         Node {
+            name: String::from(name),
             processor_component: run_component,
             input_component,
             output_nodes: HashSet::new(),
-            cached_output: RefCell::new(None),
+            bypassed: false,
+            muted: false,
+            metadata: BTreeMap::new(),
+            definition: None,
+            cached_output: SharedCell::new(OutputCache::default()),
+            dirty: SharedCell::new(true),
         }
     }
 
@@ -78,6 +99,16 @@ impl<T: Serialize> Node<T> {
         &self.input_component
     }
 
+    /// Returns the name of the node, which is unique within its graph.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Only the graph may rename its nodes, as it keeps the names unique.
+    pub(super) fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
     pub fn get_processor_name(&self) -> &str {
         match &self.processor_component {
             ProcessorComponent::BasicProcessor(p) => p.get_processor_name(),
@@ -85,10 +116,27 @@ impl<T: Serialize> Node<T> {
         }
     }
 
+    /// Returns the index of the graph input the node stands for, if its processor is a graph input.
+    pub fn get_graph_input_index(&self) -> Option<usize> {
+        match &self.processor_component {
+            ProcessorComponent::BasicProcessor(p) => p.get_graph_input_index(),
+            ProcessorComponent::SubgraphProcessor(_, _) => None,
+        }
+    }
+
+    /// Whether the processor of the node reads the frame it is evaluated at. Subgraph nodes depend
+    /// on time through the nodes of their subgraph.
+    pub fn is_time_dependent(&self) -> bool {
+        match &self.processor_component {
+            ProcessorComponent::BasicProcessor(p) => p.is_time_dependent(),
+            ProcessorComponent::SubgraphProcessor(_, _) => false,
+        }
+    }
+
     pub fn set_parameter(
         &mut self,
         parameter_name: &str,
-        parameter_value: &str,
+        parameter_value: Parameter,
     ) -> Result<(), HardeenError> {
         match &mut self.processor_component {
             ProcessorComponent::BasicProcessor(processor) => {
@@ -100,7 +148,28 @@ impl<T: Serialize> Node<T> {
         }
     }
 
-    pub fn get_parameter(&self, parameter_name: &str) -> Result<String, HardeenError> {
+    /// Parses `parameter_value` according to the declared type of the parameter and sets it.
+    pub fn set_parameter_from_str(
+        &mut self,
+        parameter_name: &str,
+        parameter_value: &str,
+    ) -> Result<(), HardeenError> {
+        let parameter_type = self.get_parameter_type(parameter_name)?;
+        self.set_parameter(parameter_name, Parameter::from_string(parameter_value, parameter_type)?)
+    }
+
+    pub fn get_parameter_type(&self, parameter_name: &str) -> Result<ParameterType, HardeenError> {
+        Ok(self.get_parameter_declaration(parameter_name)?.param_type)
+    }
+
+    pub fn get_parameter_declaration(&self, parameter_name: &str) -> Result<&ProcessorParameter, HardeenError> {
+        self.get_parameters()
+            .iter()
+            .find(|parameter| parameter.param_name == parameter_name)
+            .ok_or(HardeenError::NodeParameterDoesNotExist)
+    }
+
+    pub fn get_parameter(&self, parameter_name: &str) -> Result<Parameter, HardeenError> {
         match &self.processor_component {
             ProcessorComponent::BasicProcessor(processor) => {
                 (*processor).get_parameter(parameter_name)
@@ -140,6 +209,15 @@ impl<T: Serialize> Node<T> {
         }
     }
 
+    /// Checks whether an input could be connected to `slot_number`, which is ignored by nodes
+    /// with multiple inputs.
+    pub fn can_connect_input_node(&self, slot_number: usize) -> Result<(), HardeenError> {
+        match &self.input_component {
+            InputComponent::Multiple(_multiple_input) => Ok(()),
+            InputComponent::Slotted(slotted_input) => slotted_input.can_connect_input(slot_number),
+        }
+    }
+
     pub fn connect_input_node(
         &mut self,
         input: &NodeHandle<T>,
@@ -208,19 +286,231 @@ impl<T: Serialize> Node<T> {
         }
     }
 
+    /// Returns the inputs the node's output actually depends on: none for a muted node, the input
+    /// in slot 0 for a bypassed node and all of them otherwise. A bypassed node whose slot 0 is empty
+    /// depends on no input and outputs the default value. Multiple inputs have no order, so a bypassed
+    /// node with multiple inputs passes the one with the lowest handle through, which stays the same
+    /// when the graph is saved and loaded.
+    pub fn get_evaluated_input_handles(&self) -> Vec<NodeHandle<T>> {
+        if self.muted {
+            return Vec::new();
+        }
+
+        if self.bypassed {
+            let first_input = match &self.input_component {
+                InputComponent::Multiple(multiple_input) => multiple_input.get_all_input_handles().into_iter().min_by_key(|handle| handle.get()),
+                InputComponent::Slotted(slotted_input) => slotted_input.get_slots().first().cloned().flatten(),
+            };
+
+            return first_input.into_iter().collect();
+        }
+
+        self.get_all_input_handles()
+    }
+
+    /// Metadata is stored for editors, e.g. the position, color, collapsed state or a comment of a
+    /// node. The core does not interpret it and it does not affect the node's output.
+    pub fn get_metadata(&self) -> &BTreeMap<String, serde_json::Value> {
+        &self.metadata
+    }
+
+    pub fn get_metadata_value(&self, key: &str) -> Option<&serde_json::Value> {
+        self.metadata.get(key)
+    }
+
+    pub fn set_metadata_value(&mut self, key: &str, value: serde_json::Value) {
+        self.metadata.insert(String::from(key), value);
+    }
+
+    pub fn remove_metadata_value(&mut self, key: &str) -> Option<serde_json::Value> {
+        self.metadata.remove(key)
+    }
+
+    /// The name of the shared subgraph definition the subgraph of this node is a copy of, if any.
+    pub fn get_definition(&self) -> Option<&str> {
+        self.definition.as_deref()
+    }
+
+    pub fn set_definition(&mut self, definition: Option<String>) {
+        self.definition = definition;
+    }
+
+    /// A bypassed node passes its first input through unchanged.
+    pub fn is_bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    pub fn set_bypassed(&mut self, bypassed: bool) {
+        self.bypassed = bypassed;
+    }
+
+    /// A muted node outputs an empty result. Muting takes precedence over bypassing.
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
     pub fn get_all_outputs(&self) -> HashSet<NodeHandle<T>> {
         self.output_nodes.clone()
     }
 
-    pub fn get_cached_output(&self) -> Option<Rc<T>> {
-        (*self.cached_output.borrow()).clone()
+    /// Combines the processor type, the flags and the current parameter values with the
+    /// fingerprints of the inputs and, for subgraph processors, of the subgraph. Toggling a flag thus
+    /// changes the fingerprints of the node and everything downstream of it. Graph input nodes
+    /// combine the fingerprint of the graph input they output. Time dependent nodes also combine the
+    /// `context`, so that each frame gets a fingerprint of its own.
+    pub fn get_fingerprint(
+        &self,
+        input_fingerprints: &[u64],
+        subgraph_fingerprint: Option<u64>,
+        graph_inputs: &GraphInputs<T>,
+        context: &EvaluationContext,
+    ) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.get_processor_name().hash(&mut hasher);
+        self.bypassed.hash(&mut hasher);
+        self.muted.hash(&mut hasher);
+
+        for parameter in self.get_parameters().iter() {
+            parameter.param_name.hash(&mut hasher);
+            if let Ok(value) = self.get_parameter(parameter.param_name) {
+                value.to_string().hash(&mut hasher);
+            }
+        }
+
+        input_fingerprints.hash(&mut hasher);
+        subgraph_fingerprint.hash(&mut hasher);
+
+        if let Some(index) = self.get_graph_input_index() {
+            graph_inputs.get_fingerprint(index).hash(&mut hasher);
+        }
+
+        if self.is_time_dependent() {
+            context.hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    pub fn get_cached_output(&self, fingerprint: u64) -> Option<Shared<T>> {
+        self.cached_output.with(|cache| cache.get(fingerprint))
     }
 
-    pub fn set_cached_output(&self, data: Rc<T>) {
-        (*self.cached_output.borrow_mut()) = Some(data);
+    pub fn set_cached_output(&self, fingerprint: u64, data: Shared<T>) {
+        self.cached_output.with(|cache| cache.insert(fingerprint, data));
+    }
+
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.cached_output.with(|cache| cache.set_capacity(capacity));
     }
 
     pub fn invalidate_cache(&mut self) {
-        (*self.cached_output.borrow_mut()) = None;
+        self.cached_output.with(|cache| cache.clear());
+    }
+
+    /// A node is dirty from the moment it, or anything its output depends on, is changed until its
+    /// output is evaluated again. New nodes are dirty.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.with(|dirty| *dirty)
+    }
+
+    pub fn mark_dirty(&self) {
+        self.dirty.with(|dirty| *dirty = true);
+    }
+
+    pub(super) fn mark_clean(&self) {
+        self.dirty.with(|dirty| *dirty = false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{add_builtin_nodes, Graph, ProcessorRegistry};
+    use crate::shared::Shared;
+
+    #[test]
+    fn bypass_and_mute_nodes() {
+        let mut graph = Graph::new();
+        let [rectangle, copy] = add_builtin_nodes(&mut graph, ["CreateRectangle", "CopyPointsAndOffset"]);
+        graph.connect(&rectangle, &copy).unwrap();
+        graph.set_output_node_handle(copy.clone()).unwrap();
+
+        let copied = graph.process_graph_output(true).unwrap();
+        assert_eq!(copied.get_point_count(), 8);
+
+        graph.get_node_mut(&copy).unwrap().set_bypassed(true);
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 4);
+
+        graph.get_node_mut(&rectangle).unwrap().set_muted(true);
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 0);
+
+        let loaded = Graph::load_from_json(&graph.save_to_json().unwrap(), &ProcessorRegistry::with_builtin_processors()).unwrap();
+        assert!(loaded.get_node(&copy).unwrap().is_bypassed());
+        assert!(loaded.get_node(&rectangle).unwrap().is_muted());
+
+        graph.get_node_mut(&rectangle).unwrap().set_muted(false);
+        graph.get_node_mut(&copy).unwrap().set_bypassed(false);
+        assert!(Shared::ptr_eq(&copied, &graph.process_graph_output(true).unwrap()));
+
+        graph.disconnect(&rectangle, &copy).unwrap();
+        graph.get_node_mut(&copy).unwrap().set_muted(true);
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 0);
+    }
+
+    #[test]
+    fn bypass_passes_slot_zero_through() {
+        let mut graph = Graph::new();
+        let [rectangle, subnet] = add_builtin_nodes(&mut graph, ["CreateRectangle", "Subnet"]);
+        graph.connect_to_slot(&rectangle, &subnet, 1).unwrap();
+        graph.set_output_node_handle(subnet.clone()).unwrap();
+        graph.get_node_mut(&subnet).unwrap().set_bypassed(true);
+
+        assert!(graph.get_node(&subnet).unwrap().get_evaluated_input_handles().is_empty());
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 0);
+
+        graph.connect_to_slot(&rectangle, &subnet, 0).unwrap();
+        assert!(graph.get_node(&subnet).unwrap().get_evaluated_input_handles() == vec![rectangle.clone()]);
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 4);
+    }
+
+    #[test]
+    fn bypass_passes_the_lowest_of_multiple_inputs_through() {
+        let mut graph = Graph::new();
+        let [small, large, merge] = add_builtin_nodes(&mut graph, ["CreateRectangle", "CreateRectangle", "Merge"]);
+        graph.get_node_mut(&small).unwrap().set_parameter_from_str("width", "2").unwrap();
+        graph.connect(&large, &merge).unwrap();
+        graph.connect(&small, &merge).unwrap();
+        graph.set_output_node_handle(merge.clone()).unwrap();
+        graph.get_node_mut(&merge).unwrap().set_bypassed(true);
+
+        assert!(graph.get_node(&merge).unwrap().get_evaluated_input_handles() == vec![small.clone()]);
+        assert_eq!(graph.process_graph_output(true).unwrap().get_bounding_rect().1.0, 1.0);
+
+        let loaded = Graph::load_from_json(&graph.save_to_json().unwrap(), &ProcessorRegistry::with_builtin_processors()).unwrap();
+        assert!(loaded.get_node(&merge).unwrap().get_evaluated_input_handles() == vec![small.clone()]);
+        assert_eq!(loaded.process_graph_output(true).unwrap().get_bounding_rect().1.0, 1.0);
+    }
+
+    #[test]
+    fn keep_node_metadata() {
+        let mut graph = Graph::new();
+        let [rectangle] = add_builtin_nodes(&mut graph, ["CreateRectangle"]);
+        graph.set_output_node_handle(rectangle.clone()).unwrap();
+        let output = graph.process_graph_output(true).unwrap();
+
+        let node = graph.get_node_mut(&rectangle).unwrap();
+        node.set_metadata_value("position", serde_json::json!([120.0, 40.5]));
+        node.set_metadata_value("comment", serde_json::json!("Outline of the leaf"));
+        node.set_metadata_value("collapsed", serde_json::json!(true));
+        assert!(Shared::ptr_eq(&output, &graph.process_graph_output(true).unwrap()));
+
+        let loaded = Graph::load_from_json(&graph.save_to_json().unwrap(), &ProcessorRegistry::with_builtin_processors()).unwrap();
+        let node = loaded.get_node(&rectangle).unwrap();
+        assert_eq!(node.get_metadata(), graph.get_node(&rectangle).unwrap().get_metadata());
+        assert_eq!(node.get_metadata_value("position"), Some(&serde_json::json!([120.0, 40.5])));
     }
 }
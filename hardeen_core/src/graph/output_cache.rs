@@ -0,0 +1,99 @@
+//! # Output Cache
+//!
+//! Every node keeps the outputs of its last few evaluations. An output is stored under the
+//! fingerprint of everything it was computed from: the processor type, the parameter values and the
+//! fingerprints of the inputs. When the same fingerprint comes up again, e.g. because a parameter is
+//! set back to a previous value, the stored output is reused instead of running the processor.
+
+use crate::shared::Shared;
+use std::vec::Vec;
+
+pub const DEFAULT_CACHE_ENTRIES: usize = 4;
+
+pub struct OutputCache<T> {
+    entries: Vec<(u64, Shared<T>)>,
+    capacity: usize,
+}
+
+impl<T> OutputCache<T> {
+    pub fn new(capacity: usize) -> Self {
+        OutputCache {
+            entries: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the output stored for `fingerprint` and marks it as most recently used.
+    pub fn get(&mut self, fingerprint: u64) -> Option<Shared<T>> {
+        let position = self.entries.iter().position(|(key, _)| *key == fingerprint)?;
+        let entry = self.entries.remove(position);
+        let output = entry.1.clone();
+        self.entries.push(entry);
+
+        Some(output)
+    }
+
+    /// Stores an output. If the cache is full, the least recently used entry is dropped.
+    pub fn insert(&mut self, fingerprint: u64, output: Shared<T>) {
+        self.entries.retain(|(key, _)| *key != fingerprint);
+
+        if self.capacity == 0 {
+            return;
+        }
+
+        while self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+
+        self.entries.push((fingerprint, output));
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        if self.entries.len() > capacity {
+            self.entries.drain(..self.entries.len() - capacity);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl<T> Default for OutputCache<T> {
+    fn default() -> Self {
+        OutputCache::new(DEFAULT_CACHE_ENTRIES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_entry_is_dropped() {
+        let mut cache = OutputCache::new(2);
+
+        cache.insert(1, Shared::new("a"));
+        cache.insert(2, Shared::new("b"));
+        assert_eq!(cache.get(1).as_deref(), Some(&"a"));
+
+        cache.insert(3, Shared::new("c"));
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.get(1).as_deref(), Some(&"a"));
+        assert_eq!(cache.get(3).as_deref(), Some(&"c"));
+
+        cache.set_capacity(1);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(3).is_some());
+    }
+}
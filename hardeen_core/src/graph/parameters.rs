@@ -1,37 +1,187 @@
 //! # Parameters
 //!
 //! Each ProcessorComponent has a different set of parameters. The kind of parameters is restricted
-//! to a number of `ParameterTypes`. All parameters be set from a &str and converted to a String. This
-//! allows for a common interface to setting and getting parameters.
+//! to a number of `ParameterTypes`. Parameters are passed around as typed `Parameter` values, which
+//! are checked against the declared `ParameterType` when they are set. Every `Parameter` can also be
+//! converted to a String and parsed back without loss, which is used by the file format and the editor.
 
 use serde::Serialize;
-use std::string::ToString;
 use std::str::FromStr;
-use std::num::ParseFloatError;
 use std::fmt;
 use std::vec::Vec;
 
 use crate::geometry::Position;
+use crate::hardeen_error::HardeenError;
 
+/// How a parameter relates to the named groups of a processor's output. `Graph::validate` uses this to
+/// find group names that no upstream node creates.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum GroupUsage {
+    /// The String parameter names a group the processor reads.
+    Reads,
+    /// The String parameter names a group the processor creates, unless it is empty.
+    Creates,
+    /// If the Boolean parameter is true, the processor creates groups named by the prefix followed by
+    /// a number.
+    CreatesNumbered(&'static str)
+}
+
+/// Declaration of a processor parameter. Besides name and type it holds everything an editor needs
+/// to present the parameter. `min` and `max` apply to numbers as well as to both coordinates of
+/// positions and are enforced by `validate`, just like `options` for strings. `step` is only a hint
+/// for editors.
 #[derive(Clone, Serialize)]
 pub struct ProcessorParameter {
-    pub param_type: &'static str,
-    pub param_name: &'static str
+    pub param_type: ParameterType,
+    pub param_name: &'static str,
+    pub default_value: Parameter,
+    pub label: &'static str,
+    pub description: &'static str,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub step: Option<f32>,
+    pub options: &'static [&'static str],
+    pub group_usage: Option<GroupUsage>
 }
 
+impl ProcessorParameter {
+    pub fn new(param_name: &'static str, default_value: Parameter) -> Self {
+        ProcessorParameter {
+            param_type: default_value.get_type(),
+            param_name,
+            default_value,
+            label: param_name,
+            description: "",
+            min: None,
+            max: None,
+            step: None,
+            options: &[],
+            group_usage: None
+        }
+    }
 
-#[derive(Clone, Serialize)]
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    pub fn options(mut self, options: &'static [&'static str]) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn group_usage(mut self, group_usage: GroupUsage) -> Self {
+        self.group_usage = Some(group_usage);
+        self
+    }
+
+    /// Checks whether `value` may be assigned to this parameter.
+    pub fn validate(&self, value: &Parameter) -> Result<(), HardeenError> {
+        if value.get_type() != self.param_type {
+            return Err(HardeenError::ParameterTypeMismatch {
+                parameter_name: String::from(self.param_name),
+                expected: self.param_type,
+                found: value.get_type()
+            });
+        }
+
+        let numbers : Vec<f32> = match value {
+            Parameter::Integer(i) => vec![*i as f32],
+            Parameter::UnsignedInteger(u) => vec![*u as f32],
+            Parameter::Float(f) => vec![*f],
+            Parameter::Position(x, y) => vec![*x, *y],
+            Parameter::PositionList(list) => list.0.iter().flat_map(|p| vec![p.0, p.1]).collect(),
+            Parameter::Boolean(_) | Parameter::String(_) => Vec::new()
+        };
+
+        let in_range = |number: &f32| {
+            self.min.is_none_or(|min| *number >= min) && self.max.is_none_or(|max| *number <= max)
+        };
+
+        if !numbers.iter().all(in_range) {
+            return Err(HardeenError::ParameterOutOfRange {
+                parameter_name: String::from(self.param_name),
+                min: self.min,
+                max: self.max
+            });
+        }
+
+        if let Parameter::String(string) = value {
+            if !self.options.is_empty() && !self.options.contains(&string.as_str()) {
+                return Err(HardeenError::ParameterOptionInvalid {
+                    parameter_name: String::from(self.param_name),
+                    value: string.clone()
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Brings `value` within the declared bounds: numbers are clamped to `min` and `max` and a string
+    /// that is not one of the options is replaced by the default value. Values read from project
+    /// files go through this, so that files written before a bound was declared still load.
+    pub fn clamp(&self, value: Parameter) -> Parameter {
+        let clamp_float = |number: f32| {
+            let number = self.min.map_or(number, |min| number.max(min));
+            self.max.map_or(number, |max| number.min(max))
+        };
+
+        match value {
+            Parameter::Integer(i) => {
+                let i = self.min.map_or(i, |min| i.max(min.ceil() as i32));
+                Parameter::Integer(self.max.map_or(i, |max| i.min(max.floor() as i32)))
+            }
+            Parameter::UnsignedInteger(u) => {
+                let u = self.min.map_or(u, |min| u.max(min.ceil() as u32));
+                Parameter::UnsignedInteger(self.max.map_or(u, |max| u.min(max.floor() as u32)))
+            }
+            Parameter::Float(f) => Parameter::Float(clamp_float(f)),
+            Parameter::Position(x, y) => Parameter::Position(clamp_float(x), clamp_float(y)),
+            Parameter::PositionList(list) => Parameter::PositionList(PositionList(
+                list.0.iter().map(|p| Position(clamp_float(p.0), clamp_float(p.1))).collect()
+            )),
+            Parameter::String(string) if !self.options.is_empty() && !self.options.contains(&string.as_str()) => {
+                self.default_value.clone()
+            }
+            value => value
+        }
+    }
+}
+
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
 pub struct PositionList(pub Vec<Position>);
 
 impl FromStr for PositionList {
-    type Err = ParseFloatError;
+    type Err = HardeenError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let coords : Vec<&str> = s.trim().split(';').collect();
+        let coords : Vec<&str> = s.trim().split(';').filter(|coord| !coord.is_empty()).collect();
         let mut result : Vec<Position> = Vec::new();
 
-        for i in 0..coords.len() {
-            let position = Position::from_str(coords[i])?;
+        for coord in coords.iter() {
+            let position = Position::from_str(coord)?;
             result.push(position);
         }
 
@@ -50,7 +200,7 @@ impl fmt::Display for PositionList {
     }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
 pub enum ParameterType {
     Integer,
     UnsignedInteger,
@@ -61,7 +211,7 @@ pub enum ParameterType {
     PositionList
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Debug, PartialEq)]
 pub enum Parameter {
     Integer(i32),
     UnsignedInteger(u32),
@@ -74,61 +224,125 @@ pub enum Parameter {
 
 impl Parameter {
 
-    pub fn set(&mut self, value: &str) {
-        match self {
-            Parameter::Integer(i) => *i = value.parse::<i32>().unwrap(),
-            Parameter::Float(f) => *f = value.parse::<f32>().unwrap(),
-            _ => panic!("")
-        }
+    /// Parses `value` with the type this parameter currently has and replaces its value.
+    pub fn set(&mut self, value: &str) -> Result<(), HardeenError> {
+        *self = Parameter::from_string(value, self.get_type())?;
+        Ok(())
     }
 
-    pub fn get_type(&self) -> &str {
+    pub fn get_type(&self) -> ParameterType {
         match self {
-            Parameter::Integer(_) => "Integer",
-            Parameter::Float(_) => "Float",
-            _ => panic!("")
+            Parameter::Integer(_) => ParameterType::Integer,
+            Parameter::UnsignedInteger(_) => ParameterType::UnsignedInteger,
+            Parameter::Float(_) => ParameterType::Float,
+            Parameter::Boolean(_) => ParameterType::Boolean,
+            Parameter::Position(_, _) => ParameterType::Position,
+            Parameter::String(_) => ParameterType::String,
+            Parameter::PositionList(_) => ParameterType::PositionList,
         }
     }
 
-    pub fn from_string(value: &str, parameter_type: &str) -> Self {
+    /// Parses a parameter of the given type from its string representation, which is the one
+    /// produced by `to_string`.
+    pub fn from_string(value: &str, parameter_type: ParameterType) -> Result<Self, HardeenError> {
+        let invalid_value = || HardeenError::ParameterValueInvalid {
+            parameter_type,
+            value: String::from(value)
+        };
+
         match parameter_type {
-            "Integer" => {
-                Parameter::Integer(value.parse::<i32>().unwrap())
+            ParameterType::Integer => {
+                value.trim().parse::<i32>().map(Parameter::Integer).map_err(|_| invalid_value())
+            },
+            ParameterType::UnsignedInteger => {
+                value.trim().parse::<u32>().map(Parameter::UnsignedInteger).map_err(|_| invalid_value())
             },
-            "Unsigned" => {
-                Parameter::UnsignedInteger(value.parse::<u32>().unwrap())
+            ParameterType::Float => {
+                value.trim().parse::<f32>().map(Parameter::Float).map_err(|_| invalid_value())
             },
-            "Float" => {
-                Parameter::Float(value.parse::<f32>().unwrap())
+            ParameterType::Boolean => {
+                match value.trim() {
+                    "true" => Ok(Parameter::Boolean(true)),
+                    "false" => Ok(Parameter::Boolean(false)),
+                    _ => Err(invalid_value())
+                }
             },
-            "Boolean" => {
-                Parameter::Boolean(match value {
-                    "true" => true,
-                    "false" => false,
-                    &_ => panic!("Invalid Parameter Value!")
-                })
+            ParameterType::Position => {
+                value.parse::<Position>().map(|p| Parameter::Position(p.0, p.1))
             },
-            &_ => {
-                panic!("Invalid Parameter Type!")
+            ParameterType::String => {
+                Ok(Parameter::String(String::from(value)))
+            },
+            ParameterType::PositionList => {
+                value.parse::<PositionList>().map(Parameter::PositionList).map_err(|_| invalid_value())
             }
         }
     }
 }
 
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Integer(i) => write!(f, "{}", i),
+            Parameter::UnsignedInteger(u) => write!(f, "{}", u),
+            Parameter::Float(x) => write!(f, "{}", x),
+            Parameter::Boolean(b) => write!(f, "{}", b),
+            Parameter::Position(x, y) => write!(f, "{}", Position(*x, *y)),
+            Parameter::PositionList(list) => write!(f, "{}", list),
+            Parameter::String(string) => write!(f, "{}", string)
+        }
+    }
+}
 
+/// Rust types which can be used as processor parameters. Each of them corresponds to exactly one
+/// `ParameterType` and converts to and from the matching `Parameter` variant.
+pub trait ParameterValue: Sized {
+    const PARAMETER_TYPE: ParameterType;
 
-impl ToString for Parameter {
-    fn to_string(&self) -> String {
-        match self {
-            Parameter::Integer(i) => i.to_string(),
-            Parameter::UnsignedInteger(u) => u.to_string(),
-            Parameter::Float(f) => f.to_string(),
-            Parameter::Boolean(b) => String::from(if *b { "true" } else { "false" }),
-            Parameter::Position(x, y) => format!("({},{})", x, y),
-            Parameter::PositionList(list) => format!("{}", list),
-            Parameter::String(string) => string.clone()
+    /// Returns `None` if `parameter` is not of `PARAMETER_TYPE`.
+    fn from_parameter(parameter: Parameter) -> Option<Self>;
+    fn to_parameter(&self) -> Parameter;
+}
+
+macro_rules! impl_parameter_value {
+    ($value_type:ty, $variant:ident) => {
+        impl ParameterValue for $value_type {
+            const PARAMETER_TYPE: ParameterType = ParameterType::$variant;
+
+            fn from_parameter(parameter: Parameter) -> Option<Self> {
+                match parameter {
+                    Parameter::$variant(value) => Some(value),
+                    _ => None
+                }
+            }
+
+            fn to_parameter(&self) -> Parameter {
+                Parameter::$variant(self.clone())
+            }
+        }
+    };
+}
+
+impl_parameter_value!(i32, Integer);
+impl_parameter_value!(u32, UnsignedInteger);
+impl_parameter_value!(f32, Float);
+impl_parameter_value!(bool, Boolean);
+impl_parameter_value!(String, String);
+impl_parameter_value!(PositionList, PositionList);
+
+impl ParameterValue for Position {
+    const PARAMETER_TYPE: ParameterType = ParameterType::Position;
+
+    fn from_parameter(parameter: Parameter) -> Option<Self> {
+        match parameter {
+            Parameter::Position(x, y) => Some(Position(x, y)),
+            _ => None
         }
     }
+
+    fn to_parameter(&self) -> Parameter {
+        Parameter::Position(self.0, self.1)
+    }
 }
 
 
@@ -139,7 +353,7 @@ mod tests {
     #[test]
     fn test_float() {
         let mut m = Parameter::Float(10.0);
-        m.set("20.0");
+        m.set("20.0").unwrap();
         m.to_string();
 
         if let Parameter::Float(f) = m {
@@ -149,6 +363,82 @@ mod tests {
             assert!(false);
         }
     }
-}
 
+    #[test]
+    fn round_trip_all_types() {
+        let parameters = vec![
+            Parameter::Integer(-42),
+            Parameter::UnsignedInteger(u32::MAX),
+            Parameter::Float(0.1),
+            Parameter::Float(-1.0e-7),
+            Parameter::Boolean(true),
+            Parameter::Position(1.5, -2.25),
+            Parameter::String(String::from("a, b; (c)")),
+            Parameter::PositionList(PositionList(vec![Position(1.0, 2.0), Position(-3.5, 0.1)])),
+            Parameter::PositionList(PositionList(Vec::new())),
+        ];
+
+        for parameter in parameters {
+            let parsed = Parameter::from_string(&parameter.to_string(), parameter.get_type()).unwrap();
+            assert_eq!(parsed, parameter);
+        }
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(Parameter::from_string("1.5", ParameterType::Integer).is_err());
+        assert!(Parameter::from_string("-1", ParameterType::UnsignedInteger).is_err());
+        assert!(Parameter::from_string("yes", ParameterType::Boolean).is_err());
+        assert!(Parameter::from_string("1", ParameterType::Position).is_err());
+        assert!(Parameter::from_string("1,2,3", ParameterType::Position).is_err());
+        assert!(Parameter::from_string("1,2;3", ParameterType::PositionList).is_err());
+        assert!(matches!(
+            "1,x".parse::<Position>(),
+            Err(HardeenError::ParameterValueInvalid { parameter_type: ParameterType::Position, .. })
+        ));
+
+        let mut parameter = Parameter::Float(1.0);
+        assert!(parameter.set("abc").is_err());
+        assert_eq!(parameter, Parameter::Float(1.0));
+    }
+
+    #[test]
+    fn parameter_values() {
+        assert_eq!(f32::PARAMETER_TYPE, ParameterType::Float);
+        assert_eq!(Position(1.0, 2.0).to_parameter(), Parameter::Position(1.0, 2.0));
+        assert_eq!(Position::from_parameter(Parameter::Position(1.0, 2.0)), Some(Position(1.0, 2.0)));
+        assert_eq!(u32::from_parameter(Parameter::Integer(1)), None);
+    }
 
+    #[test]
+    fn validate_declarations() {
+        let factor = ProcessorParameter::new("factor", Parameter::Float(1.0)).min(0.0).max(2.0);
+        assert!(factor.validate(&Parameter::Float(2.0)).is_ok());
+        assert!(factor.validate(&Parameter::Float(2.5)).is_err());
+        assert!(factor.validate(&Parameter::Float(f32::NAN)).is_err());
+        assert!(factor.validate(&Parameter::Integer(1)).is_err());
+
+        let position = ProcessorParameter::new("position", Parameter::Position(0.0, 0.0)).min(-1.0);
+        assert!(position.validate(&Parameter::Position(0.0, -2.0)).is_err());
+
+        let mode = ProcessorParameter::new("mode", Parameter::String(String::from("a"))).options(&["a", "b"]);
+        assert!(mode.validate(&Parameter::String(String::from("b"))).is_ok());
+        assert!(mode.validate(&Parameter::String(String::from("c"))).is_err());
+        assert_eq!(mode.label, "mode");
+    }
+
+    #[test]
+    fn clamp_to_declarations() {
+        let count = ProcessorParameter::new("count", Parameter::UnsignedInteger(1)).min(1.0).max(4.0);
+        assert_eq!(count.clamp(Parameter::UnsignedInteger(0)), Parameter::UnsignedInteger(1));
+        assert_eq!(count.clamp(Parameter::UnsignedInteger(9)), Parameter::UnsignedInteger(4));
+        assert_eq!(count.clamp(Parameter::UnsignedInteger(3)), Parameter::UnsignedInteger(3));
+
+        let position = ProcessorParameter::new("position", Parameter::Position(0.0, 0.0)).min(-1.0);
+        assert_eq!(position.clamp(Parameter::Position(0.5, -2.0)), Parameter::Position(0.5, -1.0));
+
+        let mode = ProcessorParameter::new("mode", Parameter::String(String::from("a"))).options(&["a", "b"]);
+        assert_eq!(mode.clamp(Parameter::String(String::from("b"))), Parameter::String(String::from("b")));
+        assert_eq!(mode.clamp(Parameter::String(String::from("c"))), Parameter::String(String::from("a")));
+    }
+}
@@ -6,15 +6,20 @@
 //! another instance of a `Graph`, a subgraph. This allows for nesting and instancing of graphs within
 //! other graphs as well as looping.
 //!
+//! Both kinds of processor are run with the `EvaluationContext` the graph is evaluated in, which
+//! tells them the frame and frame rate.
+//!
 //! Note that this module provides just traits and not concrete implementations of processors. These
 //! traits are agnostic to their input type.
 
 use serde::ser::{SerializeStruct, Serializer};
 use serde::Serialize;
-use std::rc::Rc;
+use std::ops::Deref;
 
 use crate::hardeen_error::HardeenError;
+use crate::shared::{Shared, ThreadSafe};
 
+use super::context::EvaluationContext;
 use super::parameters::*;
 use super::Graph;
 use super::SubgraphHandle;
@@ -22,13 +27,15 @@ use super::SubgraphHandle;
 use super::input_component::*;
 use super::NodeHandle;
 
-pub trait Processor<T: Serialize> {
+pub trait Processor<T: Serialize>: ThreadSafe {
     fn number_inputs(&self) -> usize;
 
     fn build_input_component(&self) -> InputComponent<NodeHandle<T>>;
 
-    fn set_parameter(&mut self, param: &str, value: &str) -> Result<(), HardeenError>;
-    fn get_parameter(&self, param: &str) -> Result<String, HardeenError>;
+    /// Sets a parameter. Fails if the parameter does not exist or if `value` is not of the
+    /// declared `ParameterType`.
+    fn set_parameter(&mut self, param: &str, value: Parameter) -> Result<(), HardeenError>;
+    fn get_parameter(&self, param: &str) -> Result<Parameter, HardeenError>;
     fn get_parameters(&self) -> &[ProcessorParameter];
     fn is_parameter(&self, param: &str) -> bool;
     fn get_processor_name(&self) -> &'static str;
@@ -37,8 +44,21 @@ pub trait Processor<T: Serialize> {
 pub trait BasicProcessor<T: Serialize> : Processor<T> {
     fn run(
         &self,
-        inputs: std::vec::Vec<Rc<T>>
-    ) -> Rc<T>;
+        inputs: std::vec::Vec<Shared<T>>,
+        context: &EvaluationContext
+    ) -> Result<Shared<T>, HardeenError>;
+
+    /// Processors whose output depends on the frame of the `context` return true, so that their
+    /// outputs are cached per time.
+    fn is_time_dependent(&self) -> bool {
+        false
+    }
+
+    /// Processors that stand for an input of the graph they are in return its index. Nodes with
+    /// such a processor output the input given to the graph instead of being run.
+    fn get_graph_input_index(&self) -> Option<usize> {
+        None
+    }
 }
 
 impl<T: Serialize> Serialize for Box<dyn BasicProcessor<T>> {
@@ -53,8 +73,51 @@ impl<T: Serialize> Serialize for Box<dyn BasicProcessor<T>> {
     }
 }
 
+/// The inputs of a subgraph processor node together with the fingerprints of the nodes they come
+/// from. They are used like a slice of inputs. Handing them on to the subgraph lets its graph input
+/// nodes be fingerprinted, so the subgraph can be evaluated with caches.
+pub struct GraphInputs<T> {
+    values: std::vec::Vec<Shared<T>>,
+    fingerprints: std::vec::Vec<u64>,
+}
+
+impl<T> GraphInputs<T> {
+    /// Panics if there isn't exactly one fingerprint for each input.
+    pub fn new(values: std::vec::Vec<Shared<T>>, fingerprints: std::vec::Vec<u64>) -> Self {
+        assert_eq!(values.len(), fingerprints.len(), "Every graph input needs a fingerprint!");
+
+        GraphInputs { values, fingerprints }
+    }
+
+    pub fn get_fingerprint(&self, index: usize) -> Option<u64> {
+        self.fingerprints.get(index).copied()
+    }
+
+    pub fn into_values(self) -> std::vec::Vec<Shared<T>> {
+        self.values
+    }
+}
+
+impl<T> Deref for GraphInputs<T> {
+    type Target = [Shared<T>];
+
+    fn deref(&self) -> &[Shared<T>] {
+        &self.values
+    }
+}
+
+impl<T> Default for GraphInputs<T> {
+    fn default() -> Self {
+        GraphInputs::new(std::vec::Vec::new(), std::vec::Vec::new())
+    }
+}
+
 pub trait SubgraphProcessor<T: Serialize>: Processor<T> {
-    fn run(&self, inputs: std::vec::Vec<Rc<T>>, subgraph: &Graph<T>) -> Rc<T>;
+    /// Processors that want their subgraph to see their inputs evaluate it with
+    /// `Graph::process_graph_output_with_inputs`. Errors from evaluating `subgraph` should be passed
+    /// on unchanged, so that the node which failed within the subgraph can be located. The subgraph
+    /// is usually evaluated in the given `context`, but may be evaluated at other frames as well.
+    fn run(&self, inputs: GraphInputs<T>, subgraph: &Graph<T>, context: &EvaluationContext) -> Result<Shared<T>, HardeenError>;
 }
 
 impl<T: Serialize> Serialize for Box<dyn SubgraphProcessor<T>> {
@@ -69,6 +132,13 @@ impl<T: Serialize> Serialize for Box<dyn SubgraphProcessor<T>> {
     }
 }
 
+/// A freshly created processor that has not been added to a graph yet. Whether it is a basic or a
+/// subgraph processor decides which kind of node is created for it.
+pub enum BoxedProcessor<T: Serialize> {
+    Basic(Box<dyn BasicProcessor<T>>),
+    Subgraph(Box<dyn SubgraphProcessor<T>>),
+}
+
 #[derive(Serialize)]
 pub enum ProcessorComponent<T: Serialize> {
     BasicProcessor(Box<dyn BasicProcessor<T>>),
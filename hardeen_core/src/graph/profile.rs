@@ -0,0 +1,132 @@
+//! # Profile
+//!
+//! While a graph is profiled, every node that is needed for the output records how long it ran,
+//! whether its output came from the cache and how big that output is. Nodes inside subgraphs are
+//! recorded by their own graph and reported with the path of subgraph handles leading to them. A
+//! node that runs several times during one evaluation, like the nodes of an `InstanceOnPoints`
+//! subgraph, has a single entry with the summed wall time.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::vec::Vec;
+
+/// The size of a node's output.
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct OutputSize {
+    pub points: usize,
+    pub shapes: usize,
+    pub groups: usize,
+}
+
+/// Implemented by graph outputs that can report their size in a profile.
+pub trait MeasureOutput {
+    fn measure_output(&self) -> OutputSize;
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NodeProfile {
+    pub subgraph_path: Vec<(usize, usize)>,
+    pub node: (usize, usize),
+    pub processor_name: String,
+    pub wall_time_ms: f64,
+    pub runs: usize,
+    pub cache_hit: bool,
+    pub output_size: OutputSize,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct Profile {
+    pub wall_time_ms: f64,
+    pub nodes: Vec<NodeProfile>,
+}
+
+impl Profile {
+    /// Returns the entry of a node in the root graph, or in the subgraph at `subgraph_path`.
+    pub fn get_node_profile(&self, subgraph_path: &[(usize, usize)], node: (usize, usize)) -> Option<&NodeProfile> {
+        self.nodes
+            .iter()
+            .find(|profile| profile.subgraph_path == subgraph_path && profile.node == node)
+    }
+}
+
+/// Collects the node entries of one graph while it is profiled.
+pub(crate) struct ProfileRecorder<T> {
+    measure: fn(&T) -> OutputSize,
+    nodes: Vec<NodeProfile>,
+    index: HashMap<(usize, usize), usize>,
+}
+
+impl<T> ProfileRecorder<T> {
+    pub fn new(measure: fn(&T) -> OutputSize) -> Self {
+        ProfileRecorder {
+            measure,
+            nodes: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, node: (usize, usize), processor_name: &str, wall_time_ms: f64, cache_hit: bool, output: &T) {
+        let output_size = (self.measure)(output);
+
+        match self.index.get(&node) {
+            Some(&position) => {
+                let entry = &mut self.nodes[position];
+                entry.wall_time_ms += wall_time_ms;
+                entry.runs += if cache_hit { 0 } else { 1 };
+                entry.cache_hit = entry.cache_hit && cache_hit;
+                entry.output_size = output_size;
+            }
+            None => {
+                self.index.insert(node, self.nodes.len());
+                self.nodes.push(NodeProfile {
+                    subgraph_path: Vec::new(),
+                    node,
+                    processor_name: String::from(processor_name),
+                    wall_time_ms,
+                    runs: if cache_hit { 0 } else { 1 },
+                    cache_hit,
+                    output_size,
+                });
+            }
+        }
+    }
+
+    pub fn into_nodes(self) -> Vec<NodeProfile> {
+        self.nodes
+    }
+}
+
+/// Measures wall time. `std::time::Instant` is not available in the browser, so the wasm build
+/// asks the JavaScript clock instead.
+pub(crate) struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    start: f64,
+}
+
+impl Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start() -> Self {
+        Stopwatch {
+            start: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn start() -> Self {
+        Stopwatch {
+            start: js_sys::Date::now(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn elapsed_ms(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1000.0
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn elapsed_ms(&self) -> f64 {
+        js_sys::Date::now() - self.start
+    }
+}
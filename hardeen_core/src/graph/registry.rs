@@ -0,0 +1,103 @@
+//! # Processor Registry
+//!
+//! A `ProcessorRegistry` knows which processor types exist. For each type it stores the type's
+//! `ProcessorTypeInfo` and a factory that creates new instances of it. Graphs use a registry to add
+//! nodes by type name and to restore nodes from documents.
+//!
+//! Hardeen registers its own processors via `ProcessorRegistry::with_builtin_processors`. Other crates
+//! can register additional `BasicProcessor` and `SubgraphProcessor` types on top of these.
+
+use serde::Serialize;
+use std::vec::Vec;
+
+use crate::hardeen_error::HardeenError;
+
+use super::processor_component::*;
+use super::ProcessorTypeInfo;
+
+struct RegisteredProcessor<T: Serialize> {
+    type_info: ProcessorTypeInfo,
+    factory: Box<dyn Fn() -> BoxedProcessor<T>>,
+}
+
+pub struct ProcessorRegistry<T: Serialize> {
+    processors: Vec<RegisteredProcessor<T>>,
+}
+
+impl<T: Serialize> ProcessorRegistry<T> {
+    pub fn new() -> Self {
+        ProcessorRegistry {
+            processors: Vec::new(),
+        }
+    }
+
+    /// Registers a processor type. A type that has already been registered under the same name is
+    /// replaced.
+    pub fn register<F>(&mut self, type_info: ProcessorTypeInfo, factory: F)
+    where
+        F: Fn() -> BoxedProcessor<T> + 'static,
+    {
+        let registered_processor = RegisteredProcessor {
+            type_info,
+            factory: Box::new(factory),
+        };
+
+        match self
+            .processors
+            .iter()
+            .position(|processor| processor.type_info.get_name() == registered_processor.type_info.get_name())
+        {
+            Some(index) => self.processors[index] = registered_processor,
+            None => self.processors.push(registered_processor),
+        }
+    }
+
+    pub fn register_basic_processor<F>(&mut self, type_info: ProcessorTypeInfo, factory: F)
+    where
+        F: Fn() -> Box<dyn BasicProcessor<T>> + 'static,
+    {
+        self.register(type_info, move || BoxedProcessor::Basic(factory()));
+    }
+
+    pub fn register_subgraph_processor<F>(&mut self, type_info: ProcessorTypeInfo, factory: F)
+    where
+        F: Fn() -> Box<dyn SubgraphProcessor<T>> + 'static,
+    {
+        self.register(type_info, move || BoxedProcessor::Subgraph(factory()));
+    }
+
+    pub fn create_processor(&self, processor_type: &str) -> Result<BoxedProcessor<T>, HardeenError> {
+        match self.get_registered_processor(processor_type) {
+            Some(registered_processor) => Ok((registered_processor.factory)()),
+            None => Err(HardeenError::NodeTypeInvalid),
+        }
+    }
+
+    pub fn get_processor_type_info(&self, processor_type: &str) -> Option<&ProcessorTypeInfo> {
+        self.get_registered_processor(processor_type)
+            .map(|registered_processor| &registered_processor.type_info)
+    }
+
+    pub fn get_processor_types(&self) -> Vec<&ProcessorTypeInfo> {
+        self.processors
+            .iter()
+            .map(|registered_processor| &registered_processor.type_info)
+            .collect()
+    }
+
+    pub fn is_registered(&self, processor_type: &str) -> bool {
+        self.get_registered_processor(processor_type).is_some()
+    }
+
+    fn get_registered_processor(&self, processor_type: &str) -> Option<&RegisteredProcessor<T>> {
+        self.processors
+            .iter()
+            .find(|registered_processor| registered_processor.type_info.get_name() == processor_type)
+    }
+}
+
+impl<T: Serialize> Default for ProcessorRegistry<T> {
+    fn default() -> Self {
+        ProcessorRegistry::new()
+    }
+}
@@ -0,0 +1,329 @@
+//! # Subnets
+//!
+//! A subnet is a subgraph node whose processor is of the type "Subnet". It passes its inputs on to
+//! the "GraphInput" nodes of its subgraph, whose `index` parameter selects one of them, and outputs
+//! whatever its subgraph outputs. `Graph::collapse_into_subnet` moves a selection of nodes into a
+//! new subnet and `Graph::expand_subnet` moves the nodes of a subnet back into its graph.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::vec::Vec;
+
+use super::*;
+
+pub const SUBNET_PROCESSOR_TYPE: &str = "Subnet";
+pub const GRAPH_INPUT_PROCESSOR_TYPE: &str = "GraphInput";
+pub const GRAPH_INPUT_INDEX_PARAMETER: &str = "index";
+
+impl<T: Serialize> Graph<T> {
+    /// Replaces the given nodes by a subnet containing them. Every node outside of the selection
+    /// that feeds the selection becomes an input of the subnet, in the order of the selection. At
+    /// most one selected node may be connected to nodes outside of the selection, or be the output
+    /// of the graph; it becomes the output of the subnet. Exposed parameters of the selected nodes
+    /// are exposed by the subgraph instead.
+    pub fn collapse_into_subnet(
+        &mut self,
+        node_handles: &[NodeHandle<T>],
+        registry: &ProcessorRegistry<T>,
+    ) -> Result<NodeHandle<T>, HardeenError> {
+        let selection: Vec<NodeHandle<T>> = node_handles.iter().unique().cloned().collect();
+        let selected: HashSet<NodeHandle<T>> = selection.iter().cloned().collect();
+
+        if selection.is_empty() {
+            return Err(HardeenError::SelectionEmpty);
+        }
+
+        let mut sources: Vec<NodeHandle<T>> = Vec::new();
+        let mut boundary_inputs: Vec<(usize, NodeHandle<T>, usize)> = Vec::new();
+        let mut boundary_outputs: Vec<(NodeHandle<T>, usize)> = Vec::new();
+        let mut output_nodes: Vec<NodeHandle<T>> = Vec::new();
+        let mut sinks: Vec<NodeHandle<T>> = Vec::new();
+
+        for handle in selection.iter() {
+            let (inputs, outputs) = self.get_node_connections(handle)?;
+
+            for (from, slot) in inputs.into_iter().filter(|(from, _)| !selected.contains(from)) {
+                let index = match sources.iter().position(|source| source == &from) {
+                    Some(index) => index,
+                    None => {
+                        sources.push(from);
+                        sources.len() - 1
+                    }
+                };
+                boundary_inputs.push((index, handle.clone(), slot));
+            }
+
+            if outputs.is_empty() {
+                sinks.push(handle.clone());
+            }
+
+            let external_outputs: Vec<(NodeHandle<T>, usize)> =
+                outputs.into_iter().filter(|(to, _)| !selected.contains(to)).collect();
+
+            if !external_outputs.is_empty() || self.output_node_handle.as_ref() == Some(handle) {
+                output_nodes.push(handle.clone());
+            }
+            boundary_outputs.extend(external_outputs);
+        }
+
+        let subgraph_output = match (output_nodes.as_slice(), sinks.as_slice()) {
+            ([output_node], _) => Some(output_node.clone()),
+            ([], [sink]) => Some(sink.clone()),
+            ([], _) => None,
+            _ => return Err(HardeenError::SubnetOutputAmbiguous),
+        };
+
+        // A node outside of the selection that depends on it and feeds it at the same time would
+        // end up both before and after the subnet.
+        if boundary_outputs
+            .iter()
+            .any(|(to, _)| sources.iter().any(|source| self.path_exists(to, source)))
+        {
+            return Err(HardeenError::ConnectionCreatesCycle);
+        }
+
+        let processor = match registry.create_processor(SUBNET_PROCESSOR_TYPE)? {
+            BoxedProcessor::Subgraph(processor) => processor,
+            BoxedProcessor::Basic(_) => return Err(HardeenError::NodeTypeInvalid),
+        };
+
+        if sources.len() > processor.number_inputs() {
+            return Err(HardeenError::SubnetInputsExceeded { max: processor.number_inputs() });
+        }
+
+        let mut subgraph = Graph::new();
+        let handle_mapping = subgraph.paste_nodes(&self.copy_nodes(&selection)?, registry)?;
+
+        let mut graph_inputs = Vec::new();
+        for index in 0..sources.len() {
+            let graph_input = subgraph.add_processor_node_by_type(registry, GRAPH_INPUT_PROCESSOR_TYPE)?;
+            subgraph
+                .get_node_mut(&graph_input)?
+                .set_parameter(GRAPH_INPUT_INDEX_PARAMETER, Parameter::UnsignedInteger(index as u32))?;
+            graph_inputs.push(graph_input);
+        }
+
+        for (index, to, slot) in boundary_inputs.iter() {
+            subgraph.connect_to_slot(&graph_inputs[*index], &handle_mapping[to], *slot)?;
+        }
+
+        if let Some(subgraph_output) = subgraph_output {
+            subgraph.set_output_node_handle(handle_mapping[&subgraph_output].clone())?;
+        }
+
+        let exposed_names: Vec<String> = self
+            .exposed_parameters
+            .iter()
+            .filter(|(_, exposed_parameter)| selected.contains(exposed_parameter.get_node_handle()))
+            .map(|(exposed_name, _)| exposed_name.clone())
+            .collect();
+
+        for exposed_name in exposed_names {
+            if let Some(exposed_parameter) = self.exposed_parameters.remove(&exposed_name) {
+                subgraph.exposed_parameters.insert(
+                    exposed_name,
+                    ExposedParameter::new(&handle_mapping[exposed_parameter.get_node_handle()], exposed_parameter.get_parameter_name()),
+                );
+            }
+        }
+
+        let is_output_selected = self.output_node_handle.as_ref().is_some_and(|handle| selected.contains(handle));
+
+        for handle in selection.iter() {
+            self.take_node(handle)?;
+        }
+
+        let subgraph_handle = self.subgraphs.add_entry(subgraph);
+        let subnet_handle = self.add_node(Node::new_subgraph_processor_node(processor, subgraph_handle));
+
+        for (slot, source) in sources.iter().enumerate() {
+            self.connect_to_slot(source, &subnet_handle, slot)?;
+        }
+
+        for (to, slot) in boundary_outputs.iter() {
+            self.connect_to_slot(&subnet_handle, to, *slot)?;
+        }
+
+        if is_output_selected {
+            self.output_node_handle = Some(subnet_handle.clone());
+        }
+
+        Ok(subnet_handle)
+    }
+
+    /// Replaces a subnet by the nodes of its subgraph and returns the handles of the new nodes by
+    /// their handles within the subgraph. Nodes that were fed by graph inputs are connected to the
+    /// nodes that fed the subnet, and the nodes the subnet fed are connected to the node that was
+    /// the output of its subgraph.
+    pub fn expand_subnet(
+        &mut self,
+        node_handle: &NodeHandle<T>,
+        registry: &ProcessorRegistry<T>,
+    ) -> Result<HashMap<NodeHandle<T>, NodeHandle<T>>, HardeenError> {
+        if self.get_node(node_handle)?.get_processor_name() != SUBNET_PROCESSOR_TYPE {
+            return Err(HardeenError::NodeNotSubnet);
+        }
+
+        let subgraph_handle = self.get_subgraph_handle(node_handle)?;
+        let subgraph = self.get_subgraph(&subgraph_handle)?;
+
+        let mut inner_nodes = Vec::new();
+        let mut graph_input_indices: HashMap<NodeHandle<T>, usize> = HashMap::new();
+        let mut graph_input_outputs: Vec<(usize, NodeHandle<T>, usize)> = Vec::new();
+
+        for handle in subgraph.nodes.get_handle_iterator() {
+            match subgraph.get_node(&handle)?.get_graph_input_index() {
+                Some(index) => {
+                    let (_, outputs) = subgraph.get_node_connections(&handle)?;
+                    graph_input_outputs.extend(outputs.into_iter().map(|(to, slot)| (index, to, slot)));
+                    graph_input_indices.insert(handle, index);
+                }
+                None => inner_nodes.push(handle),
+            }
+        }
+
+        let clipboard = subgraph.copy_nodes(&inner_nodes)?;
+        let subgraph_output = subgraph.get_output_node_handle();
+        let exposed_parameters: Vec<(String, NodeHandle<T>, String)> = subgraph
+            .exposed_parameters
+            .iter()
+            .map(|(exposed_name, exposed_parameter)| {
+                (
+                    exposed_name.clone(),
+                    exposed_parameter.get_node_handle().clone(),
+                    String::from(exposed_parameter.get_parameter_name()),
+                )
+            })
+            .collect();
+
+        let handle_mapping = self.paste_nodes(&clipboard, registry)?;
+
+        let (inputs, outputs) = self.get_node_connections(node_handle)?;
+        self.take_node(node_handle)?;
+
+        // Optional slots are filled in order, so the slot of an input is also its index.
+        let get_source = |index: usize| inputs.iter().find(|(_, slot)| *slot == index).map(|(from, _)| from.clone());
+
+        for (index, to, slot) in graph_input_outputs.iter() {
+            if let Some(source) = get_source(*index) {
+                self.connect_to_slot(&source, &handle_mapping[to], *slot)?;
+            }
+        }
+
+        let new_output = subgraph_output.and_then(|subgraph_output| match graph_input_indices.get(&subgraph_output) {
+            Some(index) => get_source(*index),
+            None => handle_mapping.get(&subgraph_output).cloned(),
+        });
+
+        if let Some(new_output) = &new_output {
+            for (to, slot) in outputs.iter() {
+                self.connect_to_slot(new_output, to, *slot)?;
+            }
+        }
+
+        if self.output_node_handle.as_ref() == Some(node_handle) {
+            self.output_node_handle = new_output;
+        }
+
+        for (exposed_name, handle, parameter_name) in exposed_parameters {
+            if let (false, Some(handle)) = (self.exposed_parameters.contains_key(&exposed_name), handle_mapping.get(&handle)) {
+                self.exposed_parameters.insert(exposed_name, ExposedParameter::new(handle, &parameter_name));
+            }
+        }
+
+        Ok(handle_mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{GeometryWorld, Position};
+
+    fn get_positions(graph: &Graph<GeometryWorld>) -> Vec<Position> {
+        graph
+            .process_graph_output(false)
+            .unwrap()
+            .get_point_iterator()
+            .map(|point| point.position)
+            .collect()
+    }
+
+    #[test]
+    fn collapse_and_expand_subnet() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph: Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let translate = graph.add_processor_node_by_type(&registry, "Translate").unwrap();
+        let scale = graph.add_processor_node_by_type(&registry, "Scale").unwrap();
+        let sort = graph.add_processor_node_by_type(&registry, "SortPointsX").unwrap();
+        graph.connect(&rectangle, &translate).unwrap();
+        graph.connect(&translate, &scale).unwrap();
+        graph.connect(&scale, &sort).unwrap();
+        graph.set_output_node_handle(sort.clone()).unwrap();
+        graph.get_node_mut(&translate).unwrap().set_parameter("offset", Parameter::Position(3.0, 1.0)).unwrap();
+        graph.expose_parameter("offset", &translate, "offset").unwrap();
+
+        let positions = get_positions(&graph);
+
+        let subnet = graph.collapse_into_subnet(&[translate.clone(), scale.clone()], &registry).unwrap();
+        assert!(graph.get_node(&translate).is_err());
+        assert!(graph.get_node(&subnet).unwrap().get_all_input_handles() == vec![rectangle.clone()]);
+        assert!(graph.get_node(&sort).unwrap().get_all_input_handles() == vec![subnet.clone()]);
+        assert!(graph.set_exposed_parameter("offset", Parameter::Position(0.0, 0.0)).is_err());
+        assert_eq!(get_positions(&graph), positions);
+
+        let subgraph = graph.get_subgraph_for_node(&subnet).unwrap();
+        assert!(subgraph.get_node_handle_by_name("GraphInput1").is_some());
+        assert!(subgraph.get_output_node_handle() == subgraph.get_node_handle_by_name("Scale1"));
+        subgraph.set_exposed_parameter("offset", Parameter::Position(3.0, 1.0)).unwrap();
+
+        let mapping = graph.expand_subnet(&subnet, &registry).unwrap();
+        assert_eq!(mapping.len(), 2);
+        assert!(graph.get_node(&subnet).is_err());
+        assert_eq!(get_positions(&graph), positions);
+
+        let new_translate = graph.get_node_handle_by_name("Translate1").unwrap();
+        assert!(graph.get_node(&new_translate).unwrap().get_all_input_handles() == vec![rectangle]);
+        graph.set_exposed_parameter("offset", Parameter::Position(3.0, 1.0)).unwrap();
+
+        match graph.expand_subnet(&sort, &registry) {
+            Err(HardeenError::NodeNotSubnet) => {}
+            _ => panic!("Expected NodeNotSubnet!"),
+        }
+    }
+
+    #[test]
+    fn reject_invalid_selections() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph: Graph<GeometryWorld> = Graph::new();
+
+        let rectangle = graph.add_processor_node_by_type(&registry, "CreateRectangle").unwrap();
+        let translate = graph.add_processor_node_by_type(&registry, "Translate").unwrap();
+        let scale = graph.add_processor_node_by_type(&registry, "Scale").unwrap();
+        let merge = graph.add_processor_node_by_type(&registry, "Merge").unwrap();
+        graph.connect(&rectangle, &translate).unwrap();
+        graph.connect(&translate, &scale).unwrap();
+        graph.connect(&translate, &merge).unwrap();
+        graph.connect(&scale, &merge).unwrap();
+
+        match graph.collapse_into_subnet(&[], &registry) {
+            Err(HardeenError::SelectionEmpty) => {}
+            _ => panic!("Expected SelectionEmpty!"),
+        }
+
+        match graph.collapse_into_subnet(&[translate.clone(), scale.clone()], &registry) {
+            Err(HardeenError::SubnetOutputAmbiguous) => {}
+            _ => panic!("Expected SubnetOutputAmbiguous!"),
+        }
+
+        match graph.collapse_into_subnet(&[translate.clone(), merge.clone()], &registry) {
+            Err(HardeenError::ConnectionCreatesCycle) => {}
+            _ => panic!("Expected ConnectionCreatesCycle!"),
+        }
+
+        assert!(graph.get_node(&translate).is_ok());
+        assert!(graph.get_node(&merge).unwrap().get_all_input_handles().contains(&scale));
+    }
+}
@@ -0,0 +1,338 @@
+//! # Validation
+//!
+//! `Graph::validate` looks for problems that would otherwise only show up when the graph is
+//! evaluated. It walks the whole subgraph hierarchy and returns one `Diagnostic` per problem, each
+//! pointing at the subgraph and, if applicable, the node it was found at.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::vec::Vec;
+
+use super::{Graph, GroupUsage, NodeHandle, Parameter, ProcessorComponent};
+use crate::geometry::ALL_POINTS_GROUP_NAME;
+use crate::handled_vec::Handle;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// Evaluating the graph will fail.
+    Error,
+    /// The graph can be evaluated, but probably not as intended.
+    Warning,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum DiagnosticKind {
+    OutputNotSet,
+    InputNotSatisfied,
+    UnreachableFromOutput,
+    SubgraphOutputNotSet,
+    ExposedParameterInvalid { exposed_name: String, parameter_name: String },
+    GroupNotCreated { parameter_name: String, group_name: String },
+    GraphInputNotConnected { index: usize },
+}
+
+impl DiagnosticKind {
+    pub fn get_severity(&self) -> Severity {
+        match self {
+            DiagnosticKind::UnreachableFromOutput => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub subgraph_path: Vec<(usize, usize)>,
+    pub node: Option<(usize, usize)>,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    fn new(subgraph_path: &[(usize, usize)], node: Option<(usize, usize)>, kind: DiagnosticKind) -> Self {
+        Diagnostic {
+            severity: kind.get_severity(),
+            subgraph_path: subgraph_path.to_vec(),
+            node,
+            kind,
+        }
+    }
+}
+
+/// The group names a node's output may contain.
+#[derive(Clone, Default)]
+struct AvailableGroups {
+    names: HashSet<String>,
+    prefixes: HashSet<&'static str>,
+}
+
+impl AvailableGroups {
+    fn contains(&self, group_name: &str) -> bool {
+        group_name == ALL_POINTS_GROUP_NAME
+            || self.names.contains(group_name)
+            || self.prefixes.iter().any(|prefix| {
+                group_name
+                    .strip_prefix(prefix)
+                    .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+            })
+    }
+
+    fn extend(&mut self, other: &AvailableGroups) {
+        self.names.extend(other.names.iter().cloned());
+        self.prefixes.extend(other.prefixes.iter());
+    }
+}
+
+impl<T: Serialize> Graph<T> {
+    /// Checks the graph and all of its subgraphs and returns the problems found. An empty list
+    /// does not guarantee a successful evaluation, since processors may still fail at run time.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if !self.is_output_node_set() {
+            diagnostics.push(Diagnostic::new(&[], None, DiagnosticKind::OutputNotSet));
+        }
+
+        self.validate_graph(&[], &[], &mut diagnostics);
+
+        diagnostics
+    }
+
+    /// Validates this graph and returns the groups that may be part of its output. `graph_inputs`
+    /// holds the groups of each input of the subgraph node the graph belongs to.
+    fn validate_graph(
+        &self,
+        subgraph_path: &[(usize, usize)],
+        graph_inputs: &[AvailableGroups],
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> AvailableGroups {
+        let node_handles: Vec<NodeHandle<T>> = self.nodes.get_handle_iterator().collect();
+
+        let reachable: Option<HashSet<NodeHandle<T>>> = self
+            .output_node_handle
+            .as_ref()
+            .and_then(|output_node_handle| self.get_evaluation_order(output_node_handle).ok())
+            .map(|order| order.into_iter().collect());
+
+        for (exposed_name, exposed_parameter) in self.exposed_parameters.iter() {
+            let is_valid = self
+                .get_node(exposed_parameter.get_node_handle())
+                .map(|node| node.is_parameter(exposed_parameter.get_parameter_name()))
+                .unwrap_or(false);
+
+            if !is_valid {
+                diagnostics.push(Diagnostic::new(
+                    subgraph_path,
+                    None,
+                    DiagnosticKind::ExposedParameterInvalid {
+                        exposed_name: exposed_name.clone(),
+                        parameter_name: String::from(exposed_parameter.get_parameter_name()),
+                    },
+                ));
+            }
+        }
+
+        let order = match self.get_evaluation_order_of(&node_handles) {
+            Ok(order) => order,
+            Err(_) => return AvailableGroups::default(),
+        };
+
+        let mut available_groups: HashMap<NodeHandle<T>, AvailableGroups> = HashMap::new();
+
+        for handle in order.iter() {
+            let node = match self.get_node(handle) {
+                Ok(node) => node,
+                Err(_) => continue,
+            };
+            let location = Some(handle.get());
+
+            if !node.is_input_satisfied() && !node.is_bypassed() && !node.is_muted() {
+                diagnostics.push(Diagnostic::new(subgraph_path, location, DiagnosticKind::InputNotSatisfied));
+            }
+
+            if reachable.as_ref().is_some_and(|reachable| !reachable.contains(handle)) {
+                diagnostics.push(Diagnostic::new(subgraph_path, location, DiagnosticKind::UnreachableFromOutput));
+            }
+
+            let mut groups = AvailableGroups::default();
+            for input_handle in node.get_all_input_handles().iter() {
+                if let Some(input_groups) = available_groups.get(input_handle) {
+                    groups.extend(input_groups);
+                }
+            }
+
+            if let Some(index) = node.get_graph_input_index() {
+                match graph_inputs.get(index) {
+                    Some(input_groups) => groups.extend(input_groups),
+                    None => diagnostics.push(Diagnostic::new(
+                        subgraph_path,
+                        location,
+                        DiagnosticKind::GraphInputNotConnected { index },
+                    )),
+                }
+            }
+
+            for declaration in node.get_parameters().iter() {
+                let value = match node.get_parameter(declaration.param_name) {
+                    Ok(value) => value,
+                    Err(_) => continue,
+                };
+
+                if let (Some(GroupUsage::Reads), Parameter::String(group_name)) = (declaration.group_usage, &value) {
+                    if !groups.contains(group_name) {
+                        diagnostics.push(Diagnostic::new(
+                            subgraph_path,
+                            location,
+                            DiagnosticKind::GroupNotCreated {
+                                parameter_name: String::from(declaration.param_name),
+                                group_name: group_name.clone(),
+                            },
+                        ));
+                    }
+                }
+            }
+
+            for declaration in node.get_parameters().iter() {
+                match (declaration.group_usage, node.get_parameter(declaration.param_name)) {
+                    (Some(GroupUsage::Creates), Ok(Parameter::String(group_name))) if !group_name.is_empty() => {
+                        groups.names.insert(group_name);
+                    }
+                    (Some(GroupUsage::CreatesNumbered(prefix)), Ok(Parameter::Boolean(true))) => {
+                        groups.prefixes.insert(prefix);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let ProcessorComponent::SubgraphProcessor(_, subgraph_handle) = node.get_processor_component() {
+                if let Ok(subgraph) = self.subgraphs.get(subgraph_handle) {
+                    if !subgraph.is_output_node_set() {
+                        diagnostics.push(Diagnostic::new(subgraph_path, location, DiagnosticKind::SubgraphOutputNotSet));
+                    }
+
+                    let input_groups: Vec<AvailableGroups> = node
+                        .get_evaluated_input_handles()
+                        .iter()
+                        .map(|input_handle| available_groups.get(input_handle).cloned().unwrap_or_default())
+                        .collect();
+
+                    let mut path = subgraph_path.to_vec();
+                    path.push(subgraph_handle.get());
+                    groups.extend(&subgraph.validate_graph(&path, &input_groups, diagnostics));
+                }
+            }
+
+            available_groups.insert(handle.clone(), groups);
+        }
+
+        self.output_node_handle
+            .as_ref()
+            .and_then(|output_node_handle| available_groups.remove(output_node_handle))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::GeometryWorld;
+    use crate::graph::ProcessorRegistry;
+
+    fn has_diagnostic(diagnostics: &[Diagnostic], node: Option<&NodeHandle<GeometryWorld>>, kind: &DiagnosticKind) -> bool {
+        diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.node == node.map(|node| node.get()) && &diagnostic.kind == kind)
+    }
+
+    #[test]
+    fn report_graph_problems() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph: Graph<GeometryWorld> = Graph::new();
+
+        assert_eq!(graph.validate()[0].kind, DiagnosticKind::OutputNotSet);
+
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let shape = graph.add_processor_node_by_type(&registry, "CreateShapeFromGroup").unwrap();
+        let unconnected = graph.add_processor_node_by_type(&registry, "CreateShapeFromGroup").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &shape).unwrap();
+        graph.set_output_node_handle(shape.clone()).unwrap();
+
+        graph.get_node_mut(&shape).unwrap().set_parameter("group_name", Parameter::String(String::from("corners"))).unwrap();
+
+        let diagnostics = graph.validate();
+        assert!(has_diagnostic(&diagnostics, Some(&unconnected), &DiagnosticKind::InputNotSatisfied));
+        assert!(has_diagnostic(&diagnostics, Some(&unconnected), &DiagnosticKind::UnreachableFromOutput));
+        assert!(has_diagnostic(&diagnostics, Some(&shape), &DiagnosticKind::GroupNotCreated {
+            parameter_name: String::from("group_name"),
+            group_name: String::from("corners"),
+        }));
+        assert_eq!(diagnostics.len(), 3);
+
+        graph.remove_node(unconnected).unwrap();
+        let group = graph.add_processor_node_by_type(&registry, "GroupPoints").unwrap();
+        graph.get_node_mut(&group).unwrap().set_parameter("group_name", Parameter::String(String::from("corners"))).unwrap();
+        graph.disconnect(&points, &shape).unwrap();
+        graph.connect(&points, &group).unwrap();
+        graph.connect(&group, &shape).unwrap();
+
+        assert!(graph.validate().is_empty());
+    }
+
+    #[test]
+    fn report_subgraph_problems() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph: Graph<GeometryWorld> = Graph::new();
+
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        graph.connect(&empty, &instance).unwrap();
+        graph.set_output_node_handle(instance.clone()).unwrap();
+
+        let diagnostics = graph.validate();
+        assert!(has_diagnostic(&diagnostics, Some(&instance), &DiagnosticKind::SubgraphOutputNotSet));
+
+        let subgraph_handle = graph.get_subgraph_handle(&instance).unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let points = subgraph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        subgraph.set_output_node_handle(points.clone()).unwrap();
+
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].subgraph_path, vec![subgraph_handle.get()]);
+        assert_eq!(diagnostics[0].node, Some(points.get()));
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::InputNotSatisfied);
+    }
+    #[test]
+    fn report_graph_input_problems() {
+        let registry = ProcessorRegistry::with_builtin_processors();
+        let mut graph: Graph<GeometryWorld> = Graph::new();
+
+        let empty = graph.add_processor_node_by_type(&registry, "Empty").unwrap();
+        let points = graph.add_processor_node_by_type(&registry, "AddPoints").unwrap();
+        let group = graph.add_processor_node_by_type(&registry, "GroupPoints").unwrap();
+        let subnet = graph.add_processor_node_by_type(&registry, "Subnet").unwrap();
+        let outside = graph.add_processor_node_by_type(&registry, "GraphInput").unwrap();
+        graph.connect(&empty, &points).unwrap();
+        graph.connect(&points, &group).unwrap();
+        graph.connect_to_slot(&group, &subnet, 0).unwrap();
+        graph.set_output_node_handle(subnet.clone()).unwrap();
+        graph.get_node_mut(&group).unwrap().set_parameter("group_name", Parameter::String(String::from("corners"))).unwrap();
+
+        let subgraph = graph.get_subgraph_for_node(&subnet).unwrap();
+        let first_input = subgraph.add_processor_node_by_type(&registry, "GraphInput").unwrap();
+        let second_input = subgraph.add_processor_node_by_type(&registry, "GraphInput").unwrap();
+        let shape = subgraph.add_processor_node_by_type(&registry, "CreateShapeFromGroup").unwrap();
+        subgraph.get_node_mut(&second_input).unwrap().set_parameter("index", Parameter::UnsignedInteger(1)).unwrap();
+        subgraph.get_node_mut(&shape).unwrap().set_parameter("group_name", Parameter::String(String::from("corners"))).unwrap();
+        subgraph.connect(&first_input, &shape).unwrap();
+        subgraph.set_output_node_handle(shape.clone()).unwrap();
+
+        let diagnostics = graph.validate();
+        assert!(has_diagnostic(&diagnostics, Some(&outside), &DiagnosticKind::GraphInputNotConnected { index: 0 }));
+        assert!(has_diagnostic(&diagnostics, Some(&second_input), &DiagnosticKind::GraphInputNotConnected { index: 1 }));
+        assert!(!diagnostics.iter().any(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::GroupNotCreated { .. })));
+    }
+}
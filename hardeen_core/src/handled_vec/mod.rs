@@ -28,6 +28,7 @@ pub enum HandledVecError {
     GenerationMismatch,
     IndexDoesNotExist,
     IndexUnoccupied,
+    IndexOccupied,
     CannotBorrowAsMutable,
 }
 
@@ -65,6 +66,27 @@ impl<H: Handle + Serialize + Clone, D: DataVector> HandledVec<H, D> {
         }
     }
 
+    /// Rebuilds a HandledVec from its slots. Each slot is given by its generation and, if it is
+    /// occupied, its entry. Handles that were valid before are valid again afterwards.
+    pub fn from_slots(slots: Vec<(usize, Option<D::EntryDataType>)>) -> Self {
+        let mut handled_vec: HandledVec<H, D> = HandledVec::new();
+
+        for (index, (generation, entry)) in slots.into_iter().enumerate() {
+            match entry {
+                Some(entry) => {
+                    handled_vec.data.push(DataField::new(Some(entry), generation, true));
+                    handled_vec.entity_count += 1;
+                }
+                None => {
+                    handled_vec.data.push(DataField::new(None, generation, false));
+                    handled_vec.free_handles.push(Handle::new(index, generation));
+                }
+            }
+        }
+
+        handled_vec
+    }
+
     pub fn add_entry(&mut self, entry: D::EntryDataType) -> H {
         let handle = self.get_free_handle();
         let new_datum = DataField::new(Some(entry), handle.get_generation(), true);
@@ -103,6 +125,46 @@ impl<H: Handle + Serialize + Clone, D: DataVector> HandledVec<H, D> {
         Ok(())
     }
 
+    /// Removes an entry like `remove_entry`, but hands it back to the caller.
+    pub fn take_entry(&mut self, handle: &H) -> Result<D::EntryDataType, HandledVecError> {
+        self.get(handle)?;
+
+        let entry = match self.data.get_mut(handle.get_index()) {
+            Some(data_field) => data_field.get_data_mut().take(),
+            None => None,
+        };
+
+        self.remove_entry(handle.clone())?;
+
+        entry.ok_or(HandledVecError::IndexUnoccupied)
+    }
+
+    /// Puts an entry back into the free slot `handle` was pointing to before it was removed. This
+    /// only works as long as the slot has not been reused.
+    pub fn restore_entry(&mut self, handle: &H, entry: D::EntryDataType) -> Result<(), HandledVecError> {
+        match self.data.get(handle.get_index()) {
+            Some(data_field) => {
+                if data_field.get_generation() != handle.get_generation() {
+                    return Err(HandledVecError::GenerationMismatch);
+                }
+
+                if data_field.get_occupied() {
+                    return Err(HandledVecError::IndexOccupied);
+                }
+            }
+            None => return Err(HandledVecError::IndexDoesNotExist),
+        }
+
+        self.free_handles.retain(|free_handle| free_handle.get_index() != handle.get_index());
+        self.data.set_data_field(
+            handle.get_index(),
+            DataField::new(Some(entry), handle.get_generation(), true),
+        );
+        self.entity_count += 1;
+
+        Ok(())
+    }
+
     pub fn get(&self, handle: &H) -> Result<&D::EntryDataType, HandledVecError> {
 
         if let Some(data) = self.data.get(handle.get_index()) {
@@ -302,4 +364,37 @@ mod tests {
         assert_eq!(handled_vec.get(&invalid_handle), Err(HandledVecError::GenerationMismatch));
     }
 
+    #[test]
+    fn take_and_restore_entry() {
+        let mut handled_vec : HandledVec<MarkedHandle<MockDataField>, StdVec<MockDataField>> = HandledVec::new();
+
+        handled_vec.add_entry(MockDataField { data: 1 });
+        let h = handled_vec.add_entry(MockDataField { data: 2 });
+
+        assert_eq!(handled_vec.take_entry(&h), Ok(MockDataField { data: 2 }));
+        assert_eq!(handled_vec.entity_count, 1);
+        assert_eq!(handled_vec.get(&h), Err(HandledVecError::IndexUnoccupied));
+
+        assert_eq!(handled_vec.restore_entry(&h, MockDataField { data: 2 }), Ok(()));
+        assert_eq!(handled_vec.entity_count, 2);
+        assert_eq!(handled_vec.get(&h), Ok(&MockDataField { data: 2 }));
+        assert_eq!(handled_vec.free_handles, vec![]);
+        assert_eq!(handled_vec.restore_entry(&h, MockDataField { data: 3 }), Err(HandledVecError::IndexOccupied));
+    }
+
+    #[test]
+    fn from_slots() {
+        let handled_vec : HandledVec<MarkedHandle<MockDataField>, StdVec<MockDataField>> = HandledVec::from_slots(vec![
+            (1, Some(MockDataField { data: 1 })),
+            (3, None),
+            (2, Some(MockDataField { data: 3 })),
+        ]);
+
+        assert_eq!(handled_vec.entity_count, 2);
+        assert_eq!(handled_vec.get(&MarkedHandle::new(0, 1)), Ok(&MockDataField { data: 1 }));
+        assert_eq!(handled_vec.get(&MarkedHandle::new(2, 2)), Ok(&MockDataField { data: 3 }));
+        assert_eq!(handled_vec.get(&MarkedHandle::new(1, 3)), Err(HandledVecError::IndexUnoccupied));
+        assert_eq!(handled_vec.free_handles, vec![ MarkedHandle::new(1, 3) ]);
+    }
+
 }
\ No newline at end of file
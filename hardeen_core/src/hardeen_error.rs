@@ -1,7 +1,54 @@
-#[derive(Debug)]
+use serde::Serialize;
+
+use crate::graph::ParameterType;
+
+/// Describes a node whose evaluation failed. Handles are given as (index, generation).
+#[derive(Debug, Serialize)]
+pub struct NodeError {
+    /// Handles of the subgraphs leading from the evaluated graph to the graph containing the node.
+    pub subgraph_path: Vec<(usize, usize)>,
+    pub node: (usize, usize),
+    pub processor_name: String,
+    pub error: Box<HardeenError>,
+}
+
+#[derive(Debug, Serialize)]
 pub enum HardeenError {
     NodeSlotDoesNotExist,
     InvalidInputSlotNumber,
+    InputSlotOccupied,
+    ConnectionCreatesCycle,
+    NodesNotConnected,
+    SelectionEmpty,
+    SubnetInputsExceeded {
+        max: usize,
+    },
+    SubnetOutputAmbiguous,
+    NodeNotSubnet,
+    GraphInputNotConnected {
+        index: usize,
+    },
+    DefinitionDoesNotExist {
+        name: String,
+    },
+    DefinitionNameTaken {
+        name: String,
+    },
+    DefinitionRecursive {
+        name: String,
+    },
+    NodeNameInvalid {
+        name: String,
+    },
+    NodeNameTaken {
+        name: String,
+    },
+    NodePathInvalid {
+        path: String,
+    },
+    GraphPathInvalid {
+        path: String,
+    },
     NodeParameterDoesNotExist,
     NodeInputTypeMismatch,
     NodeRunTypeMismatch,
@@ -10,6 +57,35 @@ pub enum HardeenError {
     InvalidHandle,
     NodeTypeInvalid,
     GraphOutputNotSet,
-    ErrorProcessingNode,
+    ErrorProcessingNode(NodeError),
     ExposedParameterDoesNotExist,
+    ProjectFileInvalid,
+    ProjectFileVersionUnsupported,
+    FrameRateInvalid,
+    TransactionInProgress,
+    NoTransactionInProgress,
+    ParameterTypeMismatch {
+        parameter_name: String,
+        expected: ParameterType,
+        found: ParameterType,
+    },
+    ParameterValueInvalid {
+        parameter_type: ParameterType,
+        value: String,
+    },
+    ParameterOutOfRange {
+        parameter_name: String,
+        min: Option<f32>,
+        max: Option<f32>,
+    },
+    ParameterOptionInvalid {
+        parameter_name: String,
+        value: String,
+    },
+    GroupDoesNotExist {
+        group_name: String,
+    },
+    ProcessingFailed {
+        message: String,
+    },
 }
@@ -0,0 +1,191 @@
+//! # History
+//!
+//! `History` keeps the undo and redo stacks of a `HardeenProject`. Each entry of a stack is a list of
+//! steps that together revert one command or one transaction. The history itself does not know what
+//! a step is; it just stores them in the order they were recorded.
+//!
+//! The undo stack is bounded. Once the limit is reached the oldest entry is dropped.
+
+use std::collections::VecDeque;
+use std::vec::Vec;
+
+use crate::hardeen_error::HardeenError;
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+pub struct History<S> {
+    undo_stack: VecDeque<Vec<S>>,
+    redo_stack: Vec<Vec<S>>,
+    transaction: Vec<S>,
+    transaction_marks: Vec<usize>,
+    limit: usize,
+}
+
+impl<S> History<S> {
+    pub fn new(limit: usize) -> Self {
+        History {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            transaction: Vec::new(),
+            transaction_marks: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records the step that reverts a command which has just been executed. Any redoable entries
+    /// are discarded.
+    pub fn record(&mut self, step: S) {
+        self.redo_stack.clear();
+
+        if self.is_transaction_in_progress() {
+            self.transaction.push(step);
+        } else {
+            self.push_undo(vec![step]);
+        }
+    }
+
+    /// Starts a transaction. All steps recorded until the matching `commit_transaction` are undone
+    /// and redone together. Transactions may be nested, only the outermost one creates an entry.
+    pub fn begin_transaction(&mut self) {
+        self.transaction_marks.push(self.transaction.len());
+    }
+
+    pub fn commit_transaction(&mut self) -> Result<(), HardeenError> {
+        if self.transaction_marks.pop().is_none() {
+            return Err(HardeenError::NoTransactionInProgress);
+        }
+
+        if !self.is_transaction_in_progress() && !self.transaction.is_empty() {
+            let transaction = std::mem::take(&mut self.transaction);
+            self.push_undo(transaction);
+        }
+
+        Ok(())
+    }
+
+    /// Ends the innermost transaction and returns the steps recorded within it without creating an
+    /// entry, so the caller can revert them.
+    pub fn cancel_transaction(&mut self) -> Result<Vec<S>, HardeenError> {
+        match self.transaction_marks.pop() {
+            Some(mark) => Ok(self.transaction.split_off(mark)),
+            None => Err(HardeenError::NoTransactionInProgress),
+        }
+    }
+
+    pub fn is_transaction_in_progress(&self) -> bool {
+        !self.transaction_marks.is_empty()
+    }
+
+    pub fn take_undo(&mut self) -> Result<Option<Vec<S>>, HardeenError> {
+        if self.is_transaction_in_progress() {
+            return Err(HardeenError::TransactionInProgress);
+        }
+
+        Ok(self.undo_stack.pop_back())
+    }
+
+    pub fn take_redo(&mut self) -> Result<Option<Vec<S>>, HardeenError> {
+        if self.is_transaction_in_progress() {
+            return Err(HardeenError::TransactionInProgress);
+        }
+
+        Ok(self.redo_stack.pop())
+    }
+
+    /// Pushes an entry onto the undo stack without touching the redo stack.
+    pub fn push_undo(&mut self, steps: Vec<S>) {
+        if self.limit == 0 {
+            return;
+        }
+
+        while self.undo_stack.len() >= self.limit {
+            self.undo_stack.pop_front();
+        }
+
+        self.undo_stack.push_back(steps);
+    }
+
+    pub fn push_redo(&mut self, steps: Vec<S>) {
+        self.redo_stack.push(steps);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+
+        while self.undo_stack.len() > self.limit {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+impl<S> Default for History<S> {
+    fn default() -> Self {
+        History::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_undo_stack() {
+        let mut history : History<u32> = History::new(2);
+
+        history.record(1);
+        history.record(2);
+        history.record(3);
+
+        assert_eq!(history.take_undo().unwrap(), Some(vec![3]));
+        assert_eq!(history.take_undo().unwrap(), Some(vec![2]));
+        assert_eq!(history.take_undo().unwrap(), None);
+    }
+
+    #[test]
+    fn transactions() {
+        let mut history : History<u32> = History::default();
+
+        history.begin_transaction();
+        history.record(1);
+        history.begin_transaction();
+        history.record(2);
+        history.commit_transaction().unwrap();
+
+        assert!(history.take_undo().is_err());
+
+        history.record(3);
+        history.commit_transaction().unwrap();
+
+        assert_eq!(history.take_undo().unwrap(), Some(vec![1, 2, 3]));
+        assert!(history.commit_transaction().is_err());
+    }
+
+    #[test]
+    fn cancel_nested_transaction() {
+        let mut history : History<u32> = History::default();
+
+        history.begin_transaction();
+        history.record(1);
+        history.begin_transaction();
+        history.record(2);
+
+        assert_eq!(history.cancel_transaction().unwrap(), vec![2]);
+
+        history.commit_transaction().unwrap();
+
+        assert_eq!(history.take_undo().unwrap(), Some(vec![1]));
+    }
+}
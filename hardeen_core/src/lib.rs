@@ -18,12 +18,16 @@ mod handled_vec;
 mod hardeen_error;
 mod geometry_processors;
 mod project;
+mod history;
+mod shared;
 
 pub use crate::project::*;
+pub use crate::history::*;
+pub use crate::shared::*;
 pub use crate::graph::*;
 pub use crate::geometry_processors::*;
 pub use crate::geometry::*;
 pub use crate::handled_vec::*;
-pub use crate::hardeen_error::HardeenError;
+pub use crate::hardeen_error::{HardeenError, NodeError};
 
 pub use crate::graph::ProcessorTypeInfo;
\ No newline at end of file
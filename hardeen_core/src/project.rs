@@ -1,44 +1,232 @@
 //! # Hardeen Project
 //!
-//! A project offers an interface to work with a number of related graphs. As each graph may have a 
-//! number of subgraphs, HardeenProject provided methods to descent the tree-like structure Graphs 
-//! form.
+//! A project offers an interface to work with a number of related graphs. As each graph may have a
+//! number of subgraphs, HardeenProject provided methods to descent the tree-like structure Graphs
+//! form. Graphs are addressed by paths of node names like `/root/scatter_instances/hair`, where
+//! `root` stands for the root graph and every following name for a subgraph node within the graph
+//! before. Paths not starting with a slash are relative to the current graph and may use `..` to
+//! refer to the graph above.
+//!
+//! Edits made through the project are commands: each one records how to revert it, so it can be
+//! undone and redone. Several commands can be grouped into a transaction that is undone as a whole.
+//!
+//! The project also keeps a library of subgraph definitions its nodes share. Editing the subgraph of
+//! any node using a definition updates the definition and the subgraphs of all other nodes using it.
+//! Definitions are offered as node types next to the registered processors and can be exported to
+//! and imported from other projects.
+//!
+//! Graphs of a project are evaluated at its current frame. The project keeps the frame together with
+//! its frame rate and provides both to the evaluation as an `EvaluationContext`.
 //!
 
+use std::collections::{BTreeMap, HashMap};
 use std::vec::Vec;
 use serde::Serialize;
 
 use crate::graph::*;
+use crate::history::History;
 use crate::hardeen_error::HardeenError;
 
+/// The name the root graph goes by in graph paths.
+pub const ROOT_GRAPH_NAME: &str = "root";
+
+/// An edit together with the path of the graph it has to be applied to.
+struct RecordedEdit<T: Serialize> {
+    path: Vec<SubgraphHandle<T>>,
+    edit: GraphEdit<T>,
+}
+
 pub struct HardeenProject<T: Serialize> {
     root_graph: Graph<T>,
-    current_path: Vec<SubgraphHandle<T>>
+    current_path: Vec<SubgraphHandle<T>>,
+    registry: ProcessorRegistry<T>,
+    history: History<RecordedEdit<T>>,
+    definitions: BTreeMap<String, SubgraphDefinition<T>>,
+    frame_rate: f64,
+    frame: f64,
 }
 
 impl<T: Serialize> HardeenProject<T> {
-    pub fn new() -> Self {
+    pub fn new(registry: ProcessorRegistry<T>) -> Self {
         HardeenProject {
             root_graph: Graph::new(),
-            current_path: vec![]
+            current_path: vec![],
+            registry,
+            history: History::default(),
+            definitions: BTreeMap::new(),
+            frame_rate: DEFAULT_FRAME_RATE,
+            frame: 0.0,
         }
     }
 
-    pub fn get_current_graph_mut(&mut self) -> Result<&mut Graph<T>, HardeenError> {
-        
-        if self.current_path.is_empty() {
-            return Ok(&mut self.root_graph);
+    pub fn save_to_json(&self) -> Result<String, HardeenError> {
+        let mut project_file = ProjectFile::new(self.root_graph.to_document());
+
+        for (name, definition) in self.definitions.iter() {
+            project_file.definitions.insert(name.clone(), definition.to_document());
+        }
+
+        project_file.frame_rate = self.frame_rate;
+        project_file.frame = self.frame;
+
+        project_file.to_json()
+    }
+
+    /// Reads a project file. Subgraphs no node refers to, as left behind by earlier versions, are
+    /// dropped.
+    pub fn load_from_json(json: &str, registry: ProcessorRegistry<T>) -> Result<Self, HardeenError> {
+        let project_file = ProjectFile::from_json(json)?;
+        let mut project = HardeenProject::new(registry);
+
+        project.root_graph = Graph::from_document(&project_file.graph, &|processor_type: &str| {
+            project.registry.create_processor(processor_type)
+        })?;
+
+        project.root_graph.collect_garbage()?;
+
+        for (name, document) in project_file.definitions.iter() {
+            let mut definition = SubgraphDefinition::from_document(document, &project.registry)?;
+            definition.get_graph_mut().collect_garbage()?;
+            project.definitions.insert(name.clone(), definition);
         }
 
+        project.set_frame_rate(project_file.frame_rate)?;
+        project.frame = project_file.frame;
+
+        Ok(project)
+    }
+
+    pub fn get_registry(&self) -> &ProcessorRegistry<T> {
+        &self.registry
+    }
+
+    pub fn get_current_graph(&self) -> Result<&Graph<T>, HardeenError> {
+        self.get_graph(&self.current_path)
+    }
+
+    fn get_graph(&self, path: &[SubgraphHandle<T>]) -> Result<&Graph<T>, HardeenError> {
+        let mut current_graph = &self.root_graph;
+
+        for subgraph_handle in path.iter() {
+            current_graph = current_graph.get_subgraph(subgraph_handle)?;
+        }
+
+        Ok(current_graph)
+    }
+
+    pub fn get_current_graph_mut(&mut self) -> Result<&mut Graph<T>, HardeenError> {
+        let path = self.current_path.clone();
+        self.get_graph_mut(&path)
+    }
+
+    fn get_graph_mut(&mut self, path: &[SubgraphHandle<T>]) -> Result<&mut Graph<T>, HardeenError> {
         let mut current_graph = &mut self.root_graph;
 
-        for subgraph_handle in self.current_path.iter() {
+        for subgraph_handle in path.iter() {
             current_graph = current_graph.get_subgraph_mut(subgraph_handle)?;
         }
 
         Ok(current_graph)
     }
 
+    pub fn get_root_graph(&self) -> &Graph<T> {
+        &self.root_graph
+    }
+
+    /// Returns the handles of the subgraphs leading from the root graph to the current graph.
+    pub fn get_current_path(&self) -> &[SubgraphHandle<T>] {
+        &self.current_path
+    }
+
+    /// Returns the path of the current graph, like `/root/scatter_instances/hair`.
+    pub fn get_current_path_string(&self) -> Result<String, HardeenError> {
+        self.get_path_string(&self.current_path)
+    }
+
+    /// Returns the path of the graph the given subgraph handles lead to from the root graph.
+    pub fn get_path_string(&self, path: &[SubgraphHandle<T>]) -> Result<String, HardeenError> {
+        let mut path_string = format!("/{}", ROOT_GRAPH_NAME);
+
+        for depth in 0..path.len() {
+            let parent_graph = self.get_graph(&path[..depth])?;
+            let owner_handle = parent_graph
+                .get_subgraph_owners(&path[depth])
+                .into_iter()
+                .next()
+                .ok_or(HardeenError::InvalidHandle)?;
+
+            path_string.push('/');
+            path_string.push_str(parent_graph.get_node(&owner_handle)?.get_name());
+        }
+
+        Ok(path_string)
+    }
+
+    /// Resolves an absolute path or a path relative to the current graph into the handles of the
+    /// subgraphs leading to the graph from the root graph.
+    pub fn resolve_graph_path(&self, path: &str) -> Result<Vec<SubgraphHandle<T>>, HardeenError> {
+        let path_invalid = || HardeenError::GraphPathInvalid { path: String::from(path) };
+
+        let (mut resolved_path, relative_path) = match path.strip_prefix('/') {
+            Some(absolute_path) => {
+                let mut names = absolute_path.splitn(2, '/');
+                if names.next() != Some(ROOT_GRAPH_NAME) {
+                    return Err(path_invalid());
+                }
+                (Vec::new(), names.next().unwrap_or(""))
+            }
+            None => (self.current_path.clone(), path),
+        };
+
+        for name in relative_path.split('/').filter(|name| !name.is_empty() && *name != ".") {
+            if name == ".." {
+                resolved_path.pop().ok_or_else(path_invalid)?;
+                continue;
+            }
+
+            let graph = self.get_graph(&resolved_path)?;
+            let node_handle = graph.get_node_handle_by_name(name).ok_or_else(path_invalid)?;
+            let subgraph_handle = graph.get_subgraph_handle(&node_handle).map_err(|_| path_invalid())?;
+            resolved_path.push(subgraph_handle);
+        }
+
+        Ok(resolved_path)
+    }
+
+    /// Makes the graph at an absolute or relative path the current graph.
+    pub fn go_to_path(&mut self, path: &str) -> Result<(), HardeenError> {
+        self.current_path = self.resolve_graph_path(path)?;
+
+        Ok(())
+    }
+
+    /// Makes the subgraph of a node in the current graph the current graph.
+    pub fn go_into_node(&mut self, node_handle: &NodeHandle<T>) -> Result<(), HardeenError> {
+        let subgraph_handle = self.get_current_graph()?.get_subgraph_handle(node_handle)?;
+        self.current_path.push(subgraph_handle);
+
+        Ok(())
+    }
+
+    /// Returns the names of the subgraph nodes in the current graph, i.e. the graphs one level below,
+    /// sorted by name.
+    pub fn get_child_names(&self) -> Result<Vec<String>, HardeenError> {
+        let graph = self.get_current_graph()?;
+
+        let mut child_names: Vec<String> = graph
+            .get_node_names()
+            .into_iter()
+            .filter(|name| {
+                graph
+                    .get_node_handle_by_name(name)
+                    .is_some_and(|node_handle| graph.is_node_subgraph_processor(&node_handle).unwrap_or(false))
+            })
+            .collect();
+        child_names.sort();
+
+        Ok(child_names)
+    }
+
     pub fn go_level_up(&mut self) {
 
         if !self.current_path.is_empty() {
@@ -49,6 +237,941 @@ impl<T: Serialize> HardeenProject<T> {
     pub fn go_level_down(&mut self, subgraph_handle: SubgraphHandle<T>) {
         self.current_path.push(subgraph_handle);
     }
+
+    pub fn get_frame(&self) -> f64 {
+        self.frame
+    }
+
+    /// Sets the frame graphs are evaluated at. Nodes that depend on time get new fingerprints, so
+    /// their outputs are computed for the new frame or taken from the cache of that frame.
+    pub fn set_frame(&mut self, frame: f64) {
+        self.frame = frame;
+    }
+
+    pub fn get_frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    /// Sets the number of frames per second, which must be positive.
+    pub fn set_frame_rate(&mut self, frame_rate: f64) -> Result<(), HardeenError> {
+        if !(frame_rate > 0.0 && frame_rate.is_finite()) {
+            return Err(HardeenError::FrameRateInvalid);
+        }
+
+        self.frame_rate = frame_rate;
+
+        Ok(())
+    }
+
+    /// Returns the context graphs of the project are evaluated in at the current frame.
+    pub fn get_evaluation_context(&self) -> EvaluationContext {
+        EvaluationContext::new(self.frame, self.frame_rate)
+    }
+
+    /// Adds a node of a registered processor type or, if no processor of that name is registered,
+    /// a node using the definition of that name.
+    pub fn add_node(&mut self, processor_type: &str) -> Result<NodeHandle<T>, HardeenError> {
+        if !self.registry.is_registered(processor_type) && self.definitions.contains_key(processor_type) {
+            return self.add_definition_node(processor_type);
+        }
+
+        let processor = self.registry.create_processor(processor_type)?;
+        let node_handle = self.get_current_graph_mut()?.add_boxed_processor_node(processor);
+
+        self.history.record(RecordedEdit {
+            path: self.current_path.clone(),
+            edit: GraphEdit::RemoveNode { node_handle: node_handle.clone() },
+        });
+        self.update_definitions(&self.current_path.clone())?;
+
+        Ok(node_handle)
+    }
+
+    pub fn get_definition(&self, name: &str) -> Result<&SubgraphDefinition<T>, HardeenError> {
+        self.definitions
+            .get(name)
+            .ok_or_else(|| HardeenError::DefinitionDoesNotExist { name: String::from(name) })
+    }
+
+    fn get_definition_mut(&mut self, name: &str) -> Result<&mut SubgraphDefinition<T>, HardeenError> {
+        self.definitions
+            .get_mut(name)
+            .ok_or_else(|| HardeenError::DefinitionDoesNotExist { name: String::from(name) })
+    }
+
+    pub fn get_definition_names(&self) -> Vec<String> {
+        self.definitions.keys().cloned().collect()
+    }
+
+    /// Creates a definition from the subgraph of a node in the current graph. The node uses the new
+    /// definition from then on.
+    pub fn create_definition(&mut self, name: &str, node_handle: &NodeHandle<T>) -> Result<(), HardeenError> {
+        if self.definitions.contains_key(name) || self.registry.is_registered(name) {
+            return Err(HardeenError::DefinitionNameTaken { name: String::from(name) });
+        }
+
+        let graph = self.get_current_graph()?;
+        let subgraph = graph.get_subgraph(&graph.get_subgraph_handle(node_handle)?)?;
+        let definition = SubgraphDefinition::new(graph.get_node(node_handle)?.get_processor_name(), subgraph.duplicate(&self.registry)?);
+
+        self.definitions.insert(String::from(name), definition);
+
+        self.execute(GraphEdit::SetDefinition {
+            node_handle: node_handle.clone(),
+            definition: Some(String::from(name)),
+        })
+    }
+
+    /// Adds a node using a definition to the current graph.
+    pub fn add_definition_node(&mut self, name: &str) -> Result<NodeHandle<T>, HardeenError> {
+        let path = self.current_path.clone();
+        self.check_definition_recursion(&path, name)?;
+
+        let definition = self.get_definition(name)?;
+        let processor = match self.registry.create_processor(definition.get_processor_type())? {
+            BoxedProcessor::Subgraph(processor) => processor,
+            BoxedProcessor::Basic(_) => return Err(HardeenError::NodeRunTypeMismatch),
+        };
+        let subgraph = definition.get_graph().duplicate(&self.registry)?;
+
+        let graph = self.get_graph_mut(&path)?;
+        let node_handle = graph.add_subgraph_processor_node(processor);
+        *graph.get_subgraph_for_node(&node_handle)? = subgraph;
+        graph.get_node_mut(&node_handle)?.set_definition(Some(String::from(name)));
+
+        self.history.record(RecordedEdit {
+            path: path.clone(),
+            edit: GraphEdit::RemoveNode { node_handle: node_handle.clone() },
+        });
+        self.update_definitions(&path)?;
+
+        Ok(node_handle)
+    }
+
+    /// Returns the registered processor types followed by the definitions of the project.
+    pub fn get_node_types(&self) -> Vec<NodeTypeInfo<'_>> {
+        let processor_types = self.registry.get_processor_types().into_iter().map(NodeTypeInfo::Processor);
+        let definition_types = self
+            .definitions
+            .iter()
+            .map(|(name, definition)| NodeTypeInfo::Definition(definition.get_type_info(name)));
+
+        processor_types.chain(definition_types).collect()
+    }
+
+    /// Declares the inputs of a definition by name. There can't be more inputs than the processor of
+    /// the definition accepts.
+    pub fn set_definition_inputs(&mut self, name: &str, inputs: Vec<String>) -> Result<(), HardeenError> {
+        let max = match self.registry.create_processor(self.get_definition(name)?.get_processor_type())? {
+            BoxedProcessor::Subgraph(processor) => processor.number_inputs(),
+            BoxedProcessor::Basic(processor) => processor.number_inputs(),
+        };
+
+        if inputs.len() > max {
+            return Err(HardeenError::SubnetInputsExceeded { max });
+        }
+
+        self.get_definition_mut(name)?.set_inputs(inputs);
+
+        Ok(())
+    }
+
+    pub fn set_definition_version(&mut self, name: &str, version: u32) -> Result<(), HardeenError> {
+        self.get_definition_mut(name)?.set_version(version);
+
+        Ok(())
+    }
+
+    pub fn export_definition(&self, name: &str) -> Result<String, HardeenError> {
+        DefinitionFile::new(name, self.get_definition(name)?.to_document()).to_json()
+    }
+
+    /// Adds an exported definition to the library. A definition of the same name is replaced if the
+    /// imported one has a higher version, in which case all nodes using it are updated. Returns
+    /// whether the library changed.
+    pub fn import_definition(&mut self, json: &str) -> Result<bool, HardeenError> {
+        let definition_file = DefinitionFile::from_json(json)?;
+        let name = definition_file.name;
+
+        if self.registry.is_registered(&name) {
+            return Err(HardeenError::DefinitionNameTaken { name });
+        }
+
+        if let Some(definition) = self.definitions.get(&name) {
+            if definition.get_version() >= definition_file.definition.version {
+                return Ok(false);
+            }
+        }
+
+        let definition = SubgraphDefinition::from_document(&definition_file.definition, &self.registry)?;
+        self.update_definition_users(&name, definition, &[])?;
+
+        Ok(true)
+    }
+
+    /// Detaches a node from its definition, so that its subgraph can be edited on its own.
+    pub fn make_unique(&mut self, node_handle: &NodeHandle<T>) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::SetDefinition {
+            node_handle: node_handle.clone(),
+            definition: None,
+        })
+    }
+
+    pub fn copy_nodes(&self, node_handles: &[NodeHandle<T>]) -> Result<GraphClipboard, HardeenError> {
+        self.get_current_graph()?.copy_nodes(node_handles)
+    }
+
+    /// Pastes a clipboard into the current graph. Undoing it removes all pasted nodes at once.
+    pub fn paste_nodes(&mut self, clipboard: &GraphClipboard) -> Result<HashMap<NodeHandle<T>, NodeHandle<T>>, HardeenError> {
+        let path = self.current_path.clone();
+
+        for clipboard_node in clipboard.nodes.iter() {
+            if let Some(definition) = &clipboard_node.node.definition {
+                self.check_definition_recursion(&path, definition)?;
+            }
+        }
+
+        let mut current_graph = &mut self.root_graph;
+        for subgraph_handle in path.iter() {
+            current_graph = current_graph.get_subgraph_mut(subgraph_handle)?;
+        }
+
+        let handle_mapping = current_graph.paste_nodes(clipboard, &self.registry)?;
+
+        self.begin_transaction();
+        for node_handle in handle_mapping.values() {
+            self.history.record(RecordedEdit {
+                path: path.clone(),
+                edit: GraphEdit::RemoveNode { node_handle: node_handle.clone() },
+            });
+        }
+        self.commit_transaction()?;
+        self.update_definitions(&path)?;
+
+        Ok(handle_mapping)
+    }
+
+    pub fn duplicate_nodes(&mut self, node_handles: &[NodeHandle<T>]) -> Result<HashMap<NodeHandle<T>, NodeHandle<T>>, HardeenError> {
+        let clipboard = self.copy_nodes(node_handles)?;
+        self.paste_nodes(&clipboard)
+    }
+
+    pub fn remove_node(&mut self, node_handle: &NodeHandle<T>) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::RemoveNode { node_handle: node_handle.clone() })
+    }
+
+    /// Connects `from` to the input `slot` of `to`. A node that has been connected to this slot
+    /// before is disconnected as part of the same command.
+    pub fn connect(&mut self, from: &NodeHandle<T>, to: &NodeHandle<T>, slot: usize) -> Result<(), HardeenError> {
+        self.transaction(|project| {
+            let graph = project.get_current_graph()?;
+            graph.get_node(from)?;
+
+            let previous_input = match graph.get_node(to)?.get_input_component() {
+                InputComponent::Slotted(slotted_input) => slotted_input.get_input(slot)?,
+                InputComponent::Multiple(multiple_input) => {
+                    if multiple_input.get_all_input_handles().contains(from) {
+                        return Ok(());
+                    }
+                    None
+                }
+            };
+
+            if let Some(previous_input) = previous_input {
+                project.execute(GraphEdit::Disconnect { from: previous_input, to: to.clone(), slot })?;
+            }
+
+            project.execute(GraphEdit::Connect { from: from.clone(), to: to.clone(), slot })
+        })
+    }
+
+    pub fn disconnect(&mut self, from: &NodeHandle<T>, to: &NodeHandle<T>, slot: usize) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::Disconnect { from: from.clone(), to: to.clone(), slot })
+    }
+
+    pub fn set_parameter(&mut self, node_handle: &NodeHandle<T>, parameter_name: &str, value: Parameter) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::SetParameter {
+            node_handle: node_handle.clone(),
+            parameter_name: String::from(parameter_name),
+            value,
+        })
+    }
+
+    pub fn rename_node(&mut self, node_handle: &NodeHandle<T>, name: &str) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::RenameNode {
+            node_handle: node_handle.clone(),
+            name: String::from(name),
+        })
+    }
+
+    /// Sets a metadata value of a node, or removes it if `value` is None. Metadata doesn't change the
+    /// output, but is kept in the history and in the definitions the node belongs to.
+    pub fn set_metadata_value(&mut self, node_handle: &NodeHandle<T>, key: &str, value: Option<serde_json::Value>) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::SetMetadata {
+            node_handle: node_handle.clone(),
+            key: String::from(key),
+            value,
+        })
+    }
+
+    pub fn set_bypassed(&mut self, node_handle: &NodeHandle<T>, bypassed: bool) -> Result<(), HardeenError> {
+        let muted = self.get_current_graph()?.get_node(node_handle)?.is_muted();
+
+        self.execute(GraphEdit::SetNodeFlags { node_handle: node_handle.clone(), bypassed, muted })
+    }
+
+    pub fn set_muted(&mut self, node_handle: &NodeHandle<T>, muted: bool) -> Result<(), HardeenError> {
+        let bypassed = self.get_current_graph()?.get_node(node_handle)?.is_bypassed();
+
+        self.execute(GraphEdit::SetNodeFlags { node_handle: node_handle.clone(), bypassed, muted })
+    }
+
+    pub fn set_output_node(&mut self, node_handle: &NodeHandle<T>) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::SetOutputNode { node_handle: Some(node_handle.clone()) })
+    }
+
+    pub fn expose_parameter(&mut self, exposed_name: &str, node_handle: &NodeHandle<T>, parameter_name: &str) -> Result<(), HardeenError> {
+        self.execute(GraphEdit::ExposeParameter {
+            exposed_name: String::from(exposed_name),
+            parameter: Some((node_handle.clone(), String::from(parameter_name))),
+        })
+    }
+
+    /// Applies an edit to the current graph and records how to revert it.
+    pub fn execute(&mut self, edit: GraphEdit<T>) -> Result<(), HardeenError> {
+        let path = self.current_path.clone();
+        let reverting_edit = self.apply_edit(&path, edit)?;
+
+        self.history.record(RecordedEdit {
+            path,
+            edit: reverting_edit,
+        });
+
+        Ok(())
+    }
+
+    /// Marks nodes of the graph at `path` as dirty, together with the nodes downstream of them. As
+    /// the output of that graph changes, the same happens to the subgraph nodes leading to it, on
+    /// every level up to the root graph.
+    pub fn mark_dirty(&self, path: &[SubgraphHandle<T>], node_handles: &[NodeHandle<T>]) -> Result<(), HardeenError> {
+        let graph = self.get_graph(path)?;
+
+        for node_handle in node_handles.iter() {
+            graph.mark_dirty(node_handle)?;
+        }
+
+        for depth in (0..path.len()).rev() {
+            let parent_graph = self.get_graph(&path[..depth])?;
+
+            for owner_handle in parent_graph.get_subgraph_owners(&path[depth]).iter() {
+                parent_graph.mark_dirty(owner_handle)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies an edit to the graph at `path`, marks the nodes it changes as dirty, updates the
+    /// definitions the graph belongs to and returns the edit that reverts it.
+    fn apply_edit(&mut self, path: &[SubgraphHandle<T>], edit: GraphEdit<T>) -> Result<GraphEdit<T>, HardeenError> {
+        let graph = self.get_graph_mut(path)?;
+        let changed_nodes = edit.get_changed_nodes(graph);
+        let reverting_edit = edit.apply(graph)?;
+
+        if let Some(changed_nodes) = changed_nodes {
+            self.mark_dirty(path, &changed_nodes)?;
+        }
+        self.update_definitions(path)?;
+
+        Ok(reverting_edit)
+    }
+
+    /// Finds the innermost node on `path` that uses a definition, takes the definition from its
+    /// edited subgraph and updates the subgraphs of all other nodes using it.
+    fn update_definitions(&mut self, path: &[SubgraphHandle<T>]) -> Result<(), HardeenError> {
+        for depth in (0..path.len()).rev() {
+            let parent_graph = self.get_graph(&path[..depth])?;
+            let name = parent_graph
+                .get_subgraph_owners(&path[depth])
+                .iter()
+                .find_map(|owner_handle| parent_graph.get_node(owner_handle).ok()?.get_definition().map(String::from));
+
+            let name = match name {
+                Some(name) if self.definitions.contains_key(&name) => name,
+                _ => continue,
+            };
+
+            let source = &path[..=depth];
+            let graph = self.get_graph(source)?.duplicate(&self.registry)?;
+
+            let mut definition = match self.definitions.remove(&name) {
+                Some(definition) => definition,
+                None => return Err(HardeenError::DefinitionDoesNotExist { name }),
+            };
+            definition.set_graph(graph);
+
+            return self.update_definition_users(&name, definition, source);
+        }
+
+        Ok(())
+    }
+
+    /// Stores a definition in the library and copies it into the subgraphs of all nodes using it,
+    /// except for the subgraph at `source`.
+    fn update_definition_users(
+        &mut self,
+        name: &str,
+        definition: SubgraphDefinition<T>,
+        source: &[SubgraphHandle<T>],
+    ) -> Result<(), HardeenError> {
+        for other_definition in self.definitions.values_mut() {
+            other_definition
+                .get_graph_mut()
+                .update_definition_users(name, definition.get_graph(), &[], &self.registry)?;
+        }
+
+        let users = self
+            .root_graph
+            .update_definition_users(name, definition.get_graph(), source, &self.registry)?;
+        self.definitions.insert(String::from(name), definition);
+
+        for (user_path, node_handle) in users.iter() {
+            self.mark_dirty(user_path, std::slice::from_ref(node_handle))?;
+        }
+
+        Ok(())
+    }
+
+    /// Nodes must not use a definition within a subgraph the definition itself consists of.
+    fn check_definition_recursion(&self, path: &[SubgraphHandle<T>], name: &str) -> Result<(), HardeenError> {
+        let mut enclosing_definitions = Vec::new();
+
+        for depth in 0..path.len() {
+            let parent_graph = self.get_graph(&path[..depth])?;
+
+            for owner_handle in parent_graph.get_subgraph_owners(&path[depth]).iter() {
+                if let Some(definition) = parent_graph.get_node(owner_handle)?.get_definition() {
+                    enclosing_definitions.push(String::from(definition));
+                }
+            }
+        }
+
+        let mut pending = vec![String::from(name)];
+        let mut visited = Vec::new();
+
+        while let Some(definition_name) = pending.pop() {
+            if enclosing_definitions.contains(&definition_name) {
+                return Err(HardeenError::DefinitionRecursive { name: String::from(name) });
+            }
+
+            if let Some(definition) = self.definitions.get(&definition_name) {
+                pending.extend(definition.get_graph().get_used_definitions().into_iter().filter(|used| !visited.contains(used)));
+            }
+            visited.push(definition_name);
+        }
+
+        Ok(())
+    }
+
+    pub fn begin_transaction(&mut self) {
+        self.history.begin_transaction();
+    }
+
+    pub fn commit_transaction(&mut self) -> Result<(), HardeenError> {
+        self.history.commit_transaction()
+    }
+
+    /// Reverts all commands executed since the innermost transaction has been started.
+    pub fn cancel_transaction(&mut self) -> Result<(), HardeenError> {
+        let recorded_edits = self.history.cancel_transaction()?;
+        self.apply_recorded_edits(recorded_edits)?;
+
+        Ok(())
+    }
+
+    /// Runs `commands` within a transaction. If they fail, everything they did is reverted.
+    pub fn transaction<R, F>(&mut self, commands: F) -> Result<R, HardeenError>
+    where
+        F: FnOnce(&mut Self) -> Result<R, HardeenError>,
+    {
+        self.begin_transaction();
+
+        match commands(self) {
+            Ok(result) => {
+                self.commit_transaction()?;
+                Ok(result)
+            }
+            Err(error) => {
+                self.cancel_transaction()?;
+                Err(error)
+            }
+        }
+    }
+
+    /// Reverts the last command or transaction. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> Result<bool, HardeenError> {
+        match self.history.take_undo()? {
+            Some(recorded_edits) => {
+                let reverting_edits = self.apply_recorded_edits(recorded_edits)?;
+                self.history.push_redo(reverting_edits);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Executes the last undone command or transaction again. Returns false if there was nothing to
+    /// redo.
+    pub fn redo(&mut self) -> Result<bool, HardeenError> {
+        match self.history.take_redo()? {
+            Some(recorded_edits) => {
+                let reverting_edits = self.apply_recorded_edits(recorded_edits)?;
+                self.history.push_undo(reverting_edits);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+
+    /// Applies recorded edits in reverse order and returns the edits that revert them, in the order
+    /// they have to be applied in reverse again.
+    fn apply_recorded_edits(&mut self, recorded_edits: Vec<RecordedEdit<T>>) -> Result<Vec<RecordedEdit<T>>, HardeenError> {
+        let mut reverting_edits = Vec::new();
+
+        for RecordedEdit { path, edit } in recorded_edits.into_iter().rev() {
+            let reverting_edit = self.apply_edit(&path, edit)?;
+
+            reverting_edits.push(RecordedEdit {
+                path,
+                edit: reverting_edit,
+            });
+        }
+
+        Ok(reverting_edits)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::GeometryWorld;
+    use crate::handled_vec::Handle;
+
+    fn new_project() -> HardeenProject<GeometryWorld> {
+        HardeenProject::new(ProcessorRegistry::with_builtin_processors())
+    }
+
+    #[test]
+    fn undo_and_redo_commands() {
+        let mut project = new_project();
+
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        let scale = project.add_node("Scale").unwrap();
+        project.connect(&rectangle, &scale, 0).unwrap();
+        project.set_parameter(&scale, "factor_x", Parameter::Float(3.0)).unwrap();
+        project.set_output_node(&scale).unwrap();
+
+        assert!(project.undo().unwrap());
+        assert!(project.get_current_graph().unwrap().get_output_node_handle().is_none());
+
+        assert!(project.undo().unwrap());
+        assert_eq!(project.get_current_graph().unwrap().get_node(&scale).unwrap().get_parameter("factor_x").unwrap(), Parameter::Float(1.2));
+
+        assert!(project.undo().unwrap());
+        assert!(!project.get_current_graph().unwrap().get_node(&scale).unwrap().is_input_satisfied());
+
+        assert!(project.redo().unwrap());
+        assert!(project.redo().unwrap());
+        assert!(project.redo().unwrap());
+        assert!(!project.redo().unwrap());
+
+        let graph = project.get_current_graph().unwrap();
+        assert_eq!(graph.get_node(&scale).unwrap().get_parameter("factor_x").unwrap(), Parameter::Float(3.0));
+        assert_eq!(graph.process_graph_output(true).unwrap().get_point_count(), 4);
+    }
+
+    #[test]
+    fn undo_remove_node_restores_handle_and_connections() {
+        let mut project = new_project();
+
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        let translate = project.add_node("Translate").unwrap();
+        let scale = project.add_node("Scale").unwrap();
+        project.connect(&rectangle, &translate, 0).unwrap();
+        project.connect(&translate, &scale, 0).unwrap();
+
+        project.remove_node(&translate).unwrap();
+        assert!(project.get_current_graph().unwrap().get_node(&translate).is_err());
+        assert!(!project.get_current_graph().unwrap().get_node(&scale).unwrap().is_input_satisfied());
+
+        project.undo().unwrap();
+
+        let graph = project.get_current_graph().unwrap();
+        assert!(graph.get_node(&translate).unwrap().get_all_input_handles() == vec![rectangle.clone()]);
+        assert!(graph.get_node(&scale).unwrap().get_all_input_handles() == vec![translate.clone()]);
+        assert!(graph.get_node(&rectangle).unwrap().get_all_outputs().contains(&translate));
+
+        project.redo().unwrap();
+        assert!(project.get_current_graph().unwrap().get_node(&translate).is_err());
+    }
+
+    #[test]
+    fn connect_replaces_previous_input() {
+        let mut project = new_project();
+
+        let first = project.add_node("CreateRectangle").unwrap();
+        let second = project.add_node("ScatterPoints").unwrap();
+        let scale = project.add_node("Scale").unwrap();
+        project.connect(&first, &scale, 0).unwrap();
+        project.connect(&second, &scale, 0).unwrap();
+
+        assert!(project.get_current_graph().unwrap().get_node(&first).unwrap().get_all_outputs().is_empty());
 
+        project.undo().unwrap();
+
+        let graph = project.get_current_graph().unwrap();
+        assert!(graph.get_node(&scale).unwrap().get_all_input_handles() == vec![first.clone()]);
+        assert!(graph.get_node(&first).unwrap().get_all_outputs().contains(&scale));
+        assert!(graph.get_node(&second).unwrap().get_all_outputs().is_empty());
+    }
+
+    #[test]
+    fn transactions_are_undone_as_a_whole() {
+        let mut project = new_project();
+
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+
+        project.begin_transaction();
+        project.set_parameter(&rectangle, "width", Parameter::Float(1.0)).unwrap();
+        project.set_parameter(&rectangle, "height", Parameter::Float(2.0)).unwrap();
+        project.expose_parameter("width", &rectangle, "width").unwrap();
+        project.commit_transaction().unwrap();
+
+        project.undo().unwrap();
+
+        let node = project.get_current_graph().unwrap().get_node(&rectangle).unwrap();
+        assert_eq!(node.get_parameter("width").unwrap(), Parameter::Float(10.0));
+        assert_eq!(node.get_parameter("height").unwrap(), Parameter::Float(5.0));
+        assert!(project.get_current_graph_mut().unwrap().set_exposed_parameter("width", Parameter::Float(3.0)).is_err());
+
+        let result = project.transaction(|project| {
+            project.set_parameter(&rectangle, "width", Parameter::Float(5.0))?;
+            project.set_parameter(&rectangle, "does_not_exist", Parameter::Float(5.0))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(project.get_current_graph().unwrap().get_node(&rectangle).unwrap().get_parameter("width").unwrap(), Parameter::Float(10.0));
+    }
+
+    #[test]
+    fn commands_within_subgraphs() {
+        let mut project = new_project();
+
+        let instance = project.add_node("InstanceOnPoints").unwrap();
+        let subgraph_handle = project.get_current_graph().unwrap().get_subgraph_handle(&instance).unwrap();
+
+        project.go_level_down(subgraph_handle);
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        project.go_level_up();
+
+        project.undo().unwrap();
+
+        project.go_level_down(project.get_current_graph().unwrap().get_subgraph_handle(&instance).unwrap());
+        assert!(project.get_current_graph().unwrap().get_node(&rectangle).is_err());
+    }
+
+    #[test]
+    fn edits_in_subgraphs_dirty_their_ancestors() {
+        let mut project = new_project();
+
+        let points = project.add_node("CreateRectangle").unwrap();
+        let instance = project.add_node("InstanceOnPoints").unwrap();
+        let translate = project.add_node("Translate").unwrap();
+        project.connect(&points, &instance, 0).unwrap();
+        project.connect(&instance, &translate, 0).unwrap();
+        project.set_output_node(&translate).unwrap();
+
+        let subgraph_handle = project.get_current_graph().unwrap().get_subgraph_handle(&instance).unwrap();
+        project.go_level_down(subgraph_handle.clone());
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        project.set_output_node(&rectangle).unwrap();
+        project.go_level_up();
+
+        project.get_current_graph().unwrap().process_graph_output(true).unwrap();
+        let graph = project.get_current_graph().unwrap();
+        assert!(!graph.get_node(&points).unwrap().is_dirty());
+        assert!(!graph.get_node(&instance).unwrap().is_dirty());
+        assert!(!graph.get_node(&translate).unwrap().is_dirty());
+
+        project.go_level_down(subgraph_handle);
+        project.set_parameter(&rectangle, "width", Parameter::Float(2.0)).unwrap();
+        assert!(project.get_current_graph().unwrap().get_node(&rectangle).unwrap().is_dirty());
+        project.go_level_up();
+
+        let graph = project.get_current_graph().unwrap();
+        assert!(!graph.get_node(&points).unwrap().is_dirty());
+        assert!(graph.get_node(&instance).unwrap().is_dirty());
+        assert!(graph.get_node(&translate).unwrap().is_dirty());
+
+        graph.process_graph_output(true).unwrap();
+        assert!(!graph.get_node(&instance).unwrap().is_dirty());
+        assert!(!graph.get_node(&translate).unwrap().is_dirty());
+    }
+
+
+    #[test]
+    fn removing_a_node_removes_its_subgraph() {
+        let mut project = new_project();
+
+        let instance = project.add_node("InstanceOnPoints").unwrap();
+        let subgraph_handle = project.get_current_graph().unwrap().get_subgraph_handle(&instance).unwrap();
+
+        project.go_level_down(subgraph_handle.clone());
+        let nested_instance = project.add_node("InstanceOnPoints").unwrap();
+        project.go_level_up();
+
+        project.remove_node(&instance).unwrap();
+        assert!(project.get_current_graph().unwrap().get_subgraph(&subgraph_handle).is_err());
+
+        project.undo().unwrap();
+        let subgraph = project.get_current_graph().unwrap().get_subgraph(&subgraph_handle).unwrap();
+        assert!(subgraph.get_subgraph_handle(&nested_instance).is_ok());
+    }
+
+    #[test]
+    fn undo_restores_references_to_removed_nodes() {
+        let mut project = new_project();
+
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        let instance = project.add_node("InstanceOnPoints").unwrap();
+        project.connect(&rectangle, &instance, 0).unwrap();
+        project.set_output_node(&instance).unwrap();
+        project.expose_parameter("width", &rectangle, "width").unwrap();
+
+        project.remove_node(&rectangle).unwrap();
+        project.remove_node(&instance).unwrap();
+        let graph = project.get_current_graph().unwrap();
+        assert!(graph.get_output_node_handle().is_none());
+        assert!(graph.get_exposed_parameter("width").is_err());
+        HardeenProject::load_from_json(&project.save_to_json().unwrap(), ProcessorRegistry::with_builtin_processors()).unwrap();
+
+        project.undo().unwrap();
+        project.undo().unwrap();
+        let graph = project.get_current_graph().unwrap();
+        assert!(graph.get_output_node_handle() == Some(instance.clone()));
+        assert_eq!(graph.get_exposed_parameter("width").unwrap(), Parameter::Float(10.0));
+        assert!(graph.get_subgraph_handle(&instance).is_ok());
+
+        project.redo().unwrap();
+        assert!(project.get_current_graph().unwrap().get_exposed_parameter("width").is_err());
+    }
+
+    #[test]
+    fn collect_garbage_drops_orphaned_subgraphs() {
+        let registry: ProcessorRegistry<GeometryWorld> = ProcessorRegistry::with_builtin_processors();
+        let mut graph = Graph::new();
+
+        let instance = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        let orphan = graph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+        let subgraph = graph.get_subgraph_for_node(&instance).unwrap();
+        let nested_orphan = subgraph.add_processor_node_by_type(&registry, "InstanceOnPoints").unwrap();
+
+        // Older versions removed nodes without their subgraphs.
+        let mut document = graph.to_document();
+        document.nodes[orphan.get_index()].entry = None;
+        let subgraph_document = document.subgraphs.iter_mut().find_map(|slot| slot.entry.as_mut().filter(|entry| !entry.nodes.is_empty())).unwrap();
+        subgraph_document.nodes[nested_orphan.get_index()].entry = None;
+
+        let mut graph = Graph::from_document(&document, &|processor_type: &str| registry.create_processor(processor_type)).unwrap();
+        assert_eq!(graph.collect_garbage().unwrap(), 2);
+        assert_eq!(graph.collect_garbage().unwrap(), 0);
+        assert!(graph.get_subgraph_for_node(&instance).is_ok());
+    }
+
+
+    #[test]
+    fn definitions_are_shared_between_nodes() {
+        let mut project = new_project();
+
+        let first = project.add_node("InstanceOnPoints").unwrap();
+        let first_subgraph = project.get_current_graph().unwrap().get_subgraph_handle(&first).unwrap();
+        project.go_level_down(first_subgraph.clone());
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        project.set_output_node(&rectangle).unwrap();
+        project.expose_parameter("height", &rectangle, "height").unwrap();
+        project.go_level_up();
+
+        project.create_definition("hair", &first).unwrap();
+        assert!(matches!(project.create_definition("hair", &first), Err(HardeenError::DefinitionNameTaken { .. })));
+
+        let second = project.add_definition_node("hair").unwrap();
+        let second_subgraph = project.get_current_graph().unwrap().get_subgraph_handle(&second).unwrap();
+        project.get_current_graph_mut().unwrap().set_node_parameter(&first, "height", Parameter::Float(7.0)).unwrap();
+
+        project.go_level_down(second_subgraph.clone());
+        assert!(matches!(project.add_definition_node("hair"), Err(HardeenError::DefinitionRecursive { .. })));
+        project.set_parameter(&rectangle, "width", Parameter::Float(2.0)).unwrap();
+        project.go_level_up();
+
+        let width = |project: &HardeenProject<GeometryWorld>, subgraph_handle: &SubgraphHandle<GeometryWorld>| {
+            let subgraph = project.get_current_graph().unwrap().get_subgraph(subgraph_handle).unwrap();
+            subgraph.get_node(&rectangle).unwrap().get_parameter("width").unwrap()
+        };
+        assert_eq!(width(&project, &first_subgraph), Parameter::Float(2.0));
+        assert_eq!(project.get_definition("hair").unwrap().get_graph().get_node(&rectangle).unwrap().get_parameter("width").unwrap(), Parameter::Float(2.0));
+        assert_eq!(project.get_current_graph().unwrap().get_node_parameter(&first, "height").unwrap(), Parameter::Float(7.0));
+
+        project.make_unique(&first).unwrap();
+        project.go_level_down(second_subgraph);
+        project.set_parameter(&rectangle, "width", Parameter::Float(4.0)).unwrap();
+        project.go_level_up();
+        assert_eq!(width(&project, &first_subgraph), Parameter::Float(2.0));
+
+        let loaded = HardeenProject::load_from_json(&project.save_to_json().unwrap(), ProcessorRegistry::with_builtin_processors()).unwrap();
+        assert_eq!(loaded.get_definition_names(), vec![String::from("hair")]);
+        assert_eq!(loaded.get_current_graph().unwrap().get_node(&second).unwrap().get_definition(), Some("hair"));
+        assert!(loaded.get_current_graph().unwrap().get_node(&first).unwrap().get_definition().is_none());
+    }
+
+
+    #[test]
+    fn metadata_edits_are_commands() {
+        let mut project = new_project();
+
+        let first = project.add_node("InstanceOnPoints").unwrap();
+        let first_subgraph = project.get_current_graph().unwrap().get_subgraph_handle(&first).unwrap();
+        project.go_level_down(first_subgraph.clone());
+        let rectangle = project.add_node("CreateRectangle").unwrap();
+        project.go_level_up();
+
+        project.create_definition("hair", &first).unwrap();
+        let second = project.add_definition_node("hair").unwrap();
+        let second_subgraph = project.get_current_graph().unwrap().get_subgraph_handle(&second).unwrap();
+
+        project.go_level_down(first_subgraph.clone());
+        project.set_metadata_value(&rectangle, "color", Some(serde_json::json!("#ff0000"))).unwrap();
+        project.go_level_up();
+
+        let color = |project: &HardeenProject<GeometryWorld>, subgraph_handle: &SubgraphHandle<GeometryWorld>| {
+            let subgraph = project.get_current_graph().unwrap().get_subgraph(subgraph_handle).unwrap();
+            subgraph.get_node(&rectangle).unwrap().get_metadata_value("color").cloned()
+        };
+        assert_eq!(color(&project, &second_subgraph), Some(serde_json::json!("#ff0000")));
+
+        project.undo().unwrap();
+        assert_eq!(color(&project, &first_subgraph), None);
+        assert_eq!(color(&project, &second_subgraph), None);
+
+        project.redo().unwrap();
+        assert_eq!(color(&project, &first_subgraph), Some(serde_json::json!("#ff0000")));
+    }
+
+    #[test]
+    fn definitions_form_a_shared_library() {
+        let mut project = new_project();
+
+        let subnet = project.add_node("Subnet").unwrap();
+        let subgraph_handle = project.get_current_graph().unwrap().get_subgraph_handle(&subnet).unwrap();
+        project.go_level_down(subgraph_handle);
+        let graph_input = project.add_node("GraphInput").unwrap();
+        project.rename_node(&graph_input, "Shape").unwrap();
+        let scale = project.add_node("Scale").unwrap();
+        project.connect(&graph_input, &scale, 0).unwrap();
+        project.set_output_node(&scale).unwrap();
+        project.expose_parameter("stretch", &scale, "factor_x").unwrap();
+        project.go_level_up();
+
+        assert!(matches!(project.create_definition("Scale", &subnet), Err(HardeenError::DefinitionNameTaken { .. })));
+        project.create_definition("Stretch", &subnet).unwrap();
+        assert!(matches!(
+            project.set_definition_inputs("Stretch", (0..5).map(|index| index.to_string()).collect()),
+            Err(HardeenError::SubnetInputsExceeded { max: 4 })
+        ));
+
+        let definition_type = project.get_node_types().into_iter().find_map(|node_type| match node_type {
+            NodeTypeInfo::Definition(definition_type) => Some(definition_type),
+            NodeTypeInfo::Processor(_) => None,
+        });
+        let definition_type = definition_type.unwrap();
+        assert_eq!(definition_type.name, "Stretch");
+        assert_eq!(definition_type.version, 1);
+        assert_eq!(definition_type.inputs, vec![String::from("Shape")]);
+        assert_eq!(definition_type.parameters[0].name, "stretch");
+
+        let mut other_project = new_project();
+        let exported = project.export_definition("Stretch").unwrap();
+        assert!(other_project.import_definition(&exported).unwrap());
+        assert!(!other_project.import_definition(&exported).unwrap());
+
+        let user = other_project.add_node("Stretch").unwrap();
+        assert_eq!(other_project.get_current_graph().unwrap().get_node(&user).unwrap().get_definition(), Some("Stretch"));
+
+        project.go_level_down(project.get_current_graph().unwrap().get_subgraph_handle(&subnet).unwrap());
+        project.set_parameter(&scale, "factor_y", Parameter::Float(3.0)).unwrap();
+        project.go_level_up();
+        project.set_definition_version("Stretch", 2).unwrap();
+        assert!(other_project.import_definition(&project.export_definition("Stretch").unwrap()).unwrap());
+
+        let other_graph = other_project.get_current_graph().unwrap();
+        let user_subgraph = other_graph.get_subgraph(&other_graph.get_subgraph_handle(&user).unwrap()).unwrap();
+        assert_eq!(user_subgraph.get_node(&scale).unwrap().get_parameter("factor_y").unwrap(), Parameter::Float(3.0));
+        assert!(other_graph.get_node(&user).unwrap().is_dirty());
+    }
+
+
+    #[test]
+    fn navigate_by_graph_paths() {
+        let mut project = new_project();
+
+        let scatter = project.add_node("InstanceOnPoints").unwrap();
+        project.rename_node(&scatter, "scatter_instances").unwrap();
+        project.add_node("CreateRectangle").unwrap();
+        assert_eq!(project.get_child_names().unwrap(), vec![String::from("scatter_instances")]);
+
+        project.go_into_node(&scatter).unwrap();
+        let hair = project.add_node("Subnet").unwrap();
+        project.rename_node(&hair, "hair").unwrap();
+        project.go_to_path("hair").unwrap();
+        assert_eq!(project.get_current_path_string().unwrap(), "/root/scatter_instances/hair");
+        assert_eq!(project.get_current_path().len(), 2);
+
+        project.go_to_path("../..").unwrap();
+        assert_eq!(project.get_current_path_string().unwrap(), "/root");
+
+        project.go_to_path("/root/scatter_instances/hair").unwrap();
+        assert!(project.resolve_graph_path("..").unwrap() == project.resolve_graph_path("/root/scatter_instances").unwrap());
+
+        for invalid_path in ["/scatter_instances", "/root/CreateRectangle1", "../../..", "missing"].iter() {
+            assert!(matches!(project.go_to_path(invalid_path), Err(HardeenError::GraphPathInvalid { .. })));
+        }
+        assert_eq!(project.get_current_path_string().unwrap(), "/root/scatter_instances/hair");
+    }
+
+    #[test]
+    fn frame_and_frame_rate_are_saved() {
+        let mut project = new_project();
+        assert_eq!(project.get_evaluation_context(), EvaluationContext::default());
+
+        project.set_frame_rate(30.0).unwrap();
+        project.set_frame(45.0);
+        assert_eq!(project.get_evaluation_context().get_time(), 1.5);
+        assert!(matches!(project.set_frame_rate(0.0), Err(HardeenError::FrameRateInvalid)));
+        assert_eq!(project.get_frame_rate(), 30.0);
+
+        let loaded = HardeenProject::load_from_json(&project.save_to_json().unwrap(), ProcessorRegistry::with_builtin_processors()).unwrap();
+        assert_eq!(loaded.get_evaluation_context(), EvaluationContext::new(45.0, 30.0));
+    }
+
+}
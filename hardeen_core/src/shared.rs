@@ -0,0 +1,136 @@
+//! # Shared
+//!
+//! Outputs of nodes are shared between the nodes of a graph and their caches. By default they are
+//! reference counted with `Rc` and caches live in a `RefCell`, which is all the single-threaded
+//! wasm build needs. With the `parallel` feature `Arc` and `Mutex` are used instead and processors
+//! have to be `Send + Sync`, so that independent parts of a graph can be evaluated on several
+//! threads.
+
+#[cfg(not(feature = "parallel"))]
+use std::cell::RefCell;
+#[cfg(feature = "parallel")]
+use std::sync::Mutex;
+use std::vec::Vec;
+
+#[cfg(not(feature = "parallel"))]
+pub type Shared<T> = std::rc::Rc<T>;
+#[cfg(feature = "parallel")]
+pub type Shared<T> = std::sync::Arc<T>;
+
+/// Implemented by everything that may be used from several threads, i.e. every type without the
+/// `parallel` feature and every `Send + Sync` type with it.
+#[cfg(not(feature = "parallel"))]
+pub trait ThreadSafe {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> ThreadSafe for T {}
+
+#[cfg(feature = "parallel")]
+pub trait ThreadSafe: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync + ?Sized> ThreadSafe for T {}
+
+/// Interior mutability for caches that are filled while a graph is evaluated.
+pub struct SharedCell<T> {
+    #[cfg(not(feature = "parallel"))]
+    value: RefCell<T>,
+    #[cfg(feature = "parallel")]
+    value: Mutex<T>,
+}
+
+impl<T> SharedCell<T> {
+    #[cfg(not(feature = "parallel"))]
+    pub fn new(value: T) -> Self {
+        SharedCell {
+            value: RefCell::new(value),
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn new(value: T) -> Self {
+        SharedCell {
+            value: Mutex::new(value),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    pub fn with<R, F: FnOnce(&mut T) -> R>(&self, func: F) -> R {
+        func(&mut self.value.borrow_mut())
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn with<R, F: FnOnce(&mut T) -> R>(&self, func: F) -> R {
+        let mut value = match self.value.lock() {
+            Ok(value) => value,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        func(&mut value)
+    }
+}
+
+/// Applies `func` to all items and returns the results in the same order. With the `parallel`
+/// feature on native targets the items are distributed over as many threads as there are cores.
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+pub fn map_parallel<I, O, F>(items: Vec<I>, func: F) -> Vec<O>
+where
+    I: ThreadSafe,
+    O: ThreadSafe,
+    F: Fn(I) -> O + ThreadSafe,
+{
+    items.into_iter().map(func).collect()
+}
+
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+pub fn map_parallel<I, O, F>(items: Vec<I>, func: F) -> Vec<O>
+where
+    I: ThreadSafe,
+    O: ThreadSafe,
+    F: Fn(I) -> O + ThreadSafe,
+{
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+
+    if items.len() < 2 || threads < 2 {
+        return items.into_iter().map(func).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    let mut chunks: Vec<Vec<I>> = Vec::new();
+
+    for (index, item) in items.into_iter().enumerate() {
+        if index % chunk_size == 0 {
+            chunks.push(Vec::with_capacity(chunk_size));
+        }
+        if let Some(chunk) = chunks.last_mut() {
+            chunk.push(item);
+        }
+    }
+
+    let func = &func;
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || chunk.into_iter().map(func).collect::<Vec<O>>()))
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| match worker.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_order() {
+        let items: Vec<usize> = (0..1000).collect();
+        let results = map_parallel(items, |item| item * 2);
+
+        assert_eq!(results, (0..1000).map(|item| item * 2).collect::<Vec<usize>>());
+    }
+}
@@ -9,7 +9,6 @@ extern crate serde_derive;
 extern crate console_error_panic_hook;
 
 use std::vec::Vec;
-use std::rc::Rc;
 
 use wasm_bindgen::prelude::*;
 
@@ -81,17 +80,68 @@ export type GeometryWorld = {
     }
 }
 
+export type ParameterType = "Integer" | "UnsignedInteger" | "Float" | "Boolean" | "Position" | "String" | "PositionList";
+
 export type NodeTypeParameter = {
     param_name: string,
-    param_type: string
+    param_type: ParameterType,
+    default_value: { [type: string]: any },
+    label: string,
+    description: string,
+    min: number | null,
+    max: number | null,
+    step: number | null,
+    options: string[],
+    group_usage: "Reads" | "Creates" | { CreatesNumbered: string } | null
+}
+
+export type NodeError = {
+    subgraph_path: [number, number][],
+    node: [number, number],
+    processor_name: string,
+    error: any
+}
+
+export type Diagnostic = {
+    severity: "Error" | "Warning",
+    subgraph_path: [number, number][],
+    node: [number, number] | null,
+    kind: { type: string, [key: string]: any }
+}
+
+export type NodeProfile = {
+    subgraph_path: [number, number][],
+    node: [number, number],
+    processor_name: string,
+    wall_time_ms: number,
+    runs: number,
+    cache_hit: boolean,
+    output_size: { points: number, shapes: number, groups: number }
+}
+
+export type Profile = {
+    wall_time_ms: number,
+    nodes: NodeProfile[]
 }
 
 export type NodeInputType = { type: "Slotted", number_of_slots: number } | { type: "Multiple", zero_allowed: boolean };
 
+export type PromotedParameter = {
+    name: string,
+    declaration: NodeTypeParameter
+}
+
 export type NodeType = {
     name: string,
     input_type: NodeInputType,
     parameters: NodeTypeParameter[]
+} | {
+    name: string,
+    processor_type: string,
+    version: number,
+    input_type: NodeInputType,
+    inputs: string[],
+    parameters: PromotedParameter[]
 }
 "#;
 
@@ -151,9 +201,35 @@ impl HardeenResult {
 impl std::convert::From<HardeenError> for HardeenResult {
     fn from(error: hardeen_core::HardeenError) -> HardeenResult {
         match error {
-            HardeenError::ErrorProcessingNode => HardeenResult::new("ErrorProcessingNode"),
+            HardeenError::ErrorProcessingNode(_) => HardeenResult::new("ErrorProcessingNode"),
             HardeenError::ExposedParameterDoesNotExist => HardeenResult::new("ExposedParameterDoesNotExist"),
             HardeenError::GraphOutputNotSet => HardeenResult::new("GraphOutputNotSet"),
+            HardeenError::NodeTypeInvalid => HardeenResult::new("NodeTypeInvalid"),
+            HardeenError::NodeParameterDoesNotExist => HardeenResult::new("NodeParameterDoesNotExist"),
+            HardeenError::ParameterTypeMismatch { .. } => HardeenResult::new("ParameterTypeMismatch"),
+            HardeenError::ParameterValueInvalid { .. } => HardeenResult::new("ParameterValueInvalid"),
+            HardeenError::ParameterOutOfRange { .. } => HardeenResult::new("ParameterOutOfRange"),
+            HardeenError::ParameterOptionInvalid { .. } => HardeenResult::new("ParameterOptionInvalid"),
+            HardeenError::InvalidHandle => HardeenResult::new("InvalidHandle"),
+            HardeenError::InvalidInputSlotNumber => HardeenResult::new("InvalidInputSlotNumber"),
+            HardeenError::InputSlotOccupied => HardeenResult::new("InputSlotOccupied"),
+            HardeenError::ConnectionCreatesCycle => HardeenResult::new("ConnectionCreatesCycle"),
+            HardeenError::NodesNotConnected => HardeenResult::new("NodesNotConnected"),
+            HardeenError::SelectionEmpty => HardeenResult::new("SelectionEmpty"),
+            HardeenError::SubnetInputsExceeded { .. } => HardeenResult::new("SubnetInputsExceeded"),
+            HardeenError::SubnetOutputAmbiguous => HardeenResult::new("SubnetOutputAmbiguous"),
+            HardeenError::NodeNotSubnet => HardeenResult::new("NodeNotSubnet"),
+            HardeenError::GraphInputNotConnected { .. } => HardeenResult::new("GraphInputNotConnected"),
+            HardeenError::DefinitionDoesNotExist { .. } => HardeenResult::new("DefinitionDoesNotExist"),
+            HardeenError::DefinitionNameTaken { .. } => HardeenResult::new("DefinitionNameTaken"),
+            HardeenError::DefinitionRecursive { .. } => HardeenResult::new("DefinitionRecursive"),
+            HardeenError::NodeNameInvalid { .. } => HardeenResult::new("NodeNameInvalid"),
+            HardeenError::NodeNameTaken { .. } => HardeenResult::new("NodeNameTaken"),
+            HardeenError::NodePathInvalid { .. } => HardeenResult::new("NodePathInvalid"),
+            HardeenError::GraphPathInvalid { .. } => HardeenResult::new("GraphPathInvalid"),
+            HardeenError::ProjectFileInvalid => HardeenResult::new("ProjectFileInvalid"),
+            HardeenError::ProjectFileVersionUnsupported => HardeenResult::new("ProjectFileVersionUnsupported"),
+            HardeenError::FrameRateInvalid => HardeenResult::new("FrameRateInvalid"),
             _ => HardeenResult::new("UnknownError")
         }
     }
@@ -163,16 +239,26 @@ impl std::convert::From<HardeenError> for HardeenResult {
 #[wasm_bindgen]
 #[allow(non_snake_case)]
 pub struct HardeenCoreInterface {
-    nodeTypes: Vec<ProcessorTypeInfo>,
-    lastResult: Option<Rc<GeometryWorld>>,
-    graph: Graph<GeometryWorld>
+    project: HardeenProject<GeometryWorld>,
+    lastResult: Option<Shared<GeometryWorld>>,
+    lastError: Option<HardeenError>,
+    lastProfile: Option<Profile>,
+    profilingEnabled: bool
 }
 
-#[wasm_bindgen]
-pub struct HardeenGraphPath {
-    path: Vec<SubgraphHandle<GeometryWorld>>
+fn to_node_handle(handle: &HardeenHandle) -> NodeHandle<GeometryWorld> {
+    NodeHandle::new(handle.index, handle.generation)
+}
+
+fn to_hardeen_result(result: Result<(), HardeenError>) -> HardeenResult {
+    match result {
+        Ok(()) => HardeenResult::ok(),
+        Err(error) => HardeenResult::from(error)
+    }
 }
 
+/// All calls refer to the current graph of the project, which is changed by `go_to_path`,
+/// `go_into_node` and `go_level_up`.
 #[wasm_bindgen]
 impl HardeenCoreInterface {
 
@@ -180,199 +266,302 @@ impl HardeenCoreInterface {
         console_error_panic_hook::set_once();
 
         HardeenCoreInterface {
-            nodeTypes: vec![
-                hardeen_core::Empty::get_processor_type_info(),
-                hardeen_core::CreateRectangle::get_processor_type_info(),
-                hardeen_core::ScatterPoints::get_processor_type_info(),
-                hardeen_core::Scale::get_processor_type_info(),
-                hardeen_core::RandomTangents::get_processor_type_info(),
-                hardeen_core::AddPoints::get_processor_type_info(),
-                hardeen_core::Merge::get_processor_type_info(),
-                hardeen_core::CopyPointsAndOffset::get_processor_type_info(),
-                hardeen_core::SortPointsX::get_processor_type_info(),
-                hardeen_core::CreateShapeFromGroup::get_processor_type_info(),
-                hardeen_core::Translate::get_processor_type_info(),
-                hardeen_core::RandomTranslate::get_processor_type_info(),
-                hardeen_core::CopyPointsAndRandomOffset::get_processor_type_info(),
-                hardeen_core::CreateShapeFromAllGroups::get_processor_type_info(),
-                hardeen_core::SmoothTangents::get_processor_type_info(),
-                hardeen_core::ExtrudeShape::get_processor_type_info(),
-                hardeen_core::InstanceOnPoints::get_processor_type_info(),
-            ],
+            project: HardeenProject::new(ProcessorRegistry::with_builtin_processors()),
             lastResult: None,
-            graph: Graph::new()
+            lastError: None,
+            lastProfile: None,
+            profilingEnabled: false
         }
     }
 
-    pub fn get_root_path(&self) -> HardeenGraphPath {
-        HardeenGraphPath {
-            path: vec![]
+    /// Returns the path of the current graph, like `/root/scatter_instances/hair`.
+    pub fn get_current_path(&self) -> String {
+        match self.project.get_current_path_string() {
+            Ok(path) => path,
+            Err(_) => format!("/{}", ROOT_GRAPH_NAME)
         }
     }
 
-    fn get_subgraph_from_path_mut(&mut self, graph_path: &HardeenGraphPath) -> &mut Graph<GeometryWorld> {
-        if graph_path.path.len() == 0 {
-            &mut self.graph
-        }
-        else {
-            let mut parent = &mut self.graph;
-            for subgraph_handle in graph_path.path.iter().rev() {
-                parent = parent.get_subgraph_mut(subgraph_handle).unwrap()
-            }
+    /// Makes the graph at an absolute path or a path relative to the current graph the current graph.
+    pub fn go_to_path(&mut self, path: &str) -> HardeenResult {
+        to_hardeen_result(self.project.go_to_path(path))
+    }
 
-            parent
-        }
+    pub fn go_into_node(&mut self, handle: &HardeenHandle) -> HardeenResult {
+        to_hardeen_result(self.project.go_into_node(&to_node_handle(handle)))
     }
 
-    fn get_subgraph_from_path(&self, graph_path: &HardeenGraphPath) -> &Graph<GeometryWorld> {
-        if graph_path.path.len() == 0 {
-            &self.graph
-        }
-        else {
-            let mut parent = &self.graph;
-            for subgraph_handle in graph_path.path.iter().rev() {
-                parent = parent.get_subgraph(subgraph_handle).unwrap()
-            }
+    pub fn go_level_up(&mut self) {
+        self.project.go_level_up();
+    }
 
-            parent
+    /// Returns the names of the subgraph nodes in the current graph.
+    pub fn get_children(&self) -> JsValue {
+        match self.project.get_child_names() {
+            Ok(child_names) => JsValue::from_serde(&child_names).unwrap(),
+            Err(_) => JsValue::from_serde(&Vec::<String>::new()).unwrap()
         }
     }
 
-    pub fn hash_graph_path(&self, graph_path: &HardeenGraphPath) -> JsValue {
+    pub fn get_output_node(&self) -> Option<HardeenHandle> {
+        let graph = self.project.get_current_graph().ok()?;
 
-        let mut parent = &self.graph;
-        let mut hash = String::from("r");
-        for subgraph_handle in graph_path.path.iter() {
-            parent = parent.get_subgraph(subgraph_handle).unwrap();
-            hash.push_str(&subgraph_handle.get_index().to_string());
-            hash.push_str(&subgraph_handle.get_generation().to_string());
+        match graph.get_output_node_handle() {
+            Some(handle) => Some(HardeenHandle::new(handle.get_index(), handle.get_generation(), "unknown")),
+            None => None
         }
-        JsValue::from(hash)
     }
 
-    pub fn get_graph_path(&self, parent_path: &HardeenGraphPath, handle: &HardeenHandle) -> HardeenGraphPath {
-        let graph = self.get_subgraph_from_path(parent_path);
-        let subgraph_handle = graph.get_subgraph_handle(&NodeHandle::new(handle.index, handle.generation)).unwrap();
-        let mut path = parent_path.path.clone();
-        path.push(subgraph_handle);
+    pub fn set_output_node(&mut self, handle: &HardeenHandle) -> HardeenResult {
+        let handle = to_node_handle(handle);
 
-        HardeenGraphPath {
-            path
+        if let Ok(graph) = self.project.get_current_graph() {
+            if graph.get_output_node_handle().as_ref() == Some(&handle) {
+                return HardeenResult::ok();
+            }
         }
+
+        to_hardeen_result(self.project.set_output_node(&handle))
     }
 
-    pub fn get_output_node(&self, path: &HardeenGraphPath) -> Option<HardeenHandle> {
-        let graph = self.get_subgraph_from_path(path);
+    pub fn is_node_subgraph_processor(&self, handle: &HardeenHandle) -> bool {
+        match self.project.get_current_graph() {
+            Ok(graph) => graph.is_node_subgraph_processor(&to_node_handle(handle)).unwrap_or(false),
+            Err(_) => false
+        }
+    }
 
-        match graph.get_output_node_handle() {
-            Some(handle) => Some(HardeenHandle::new(handle.get_index(), handle.get_generation(), "unknown")),
-            None => None
+    /// Adds a node of a registered processor type or of a definition of the project.
+    #[allow(non_snake_case)]
+    pub fn add_processor_node(&mut self, typeName: &str) -> Result<HardeenHandle, JsValue> {
+        match self.project.add_node(typeName) {
+            Ok(handle) => Ok(HardeenHandle::new(handle.get_index(), handle.get_generation(), typeName)),
+            Err(error) => Err(JsValue::from_str(&HardeenResult::from(error).getResultType()))
         }
     }
 
-    pub fn set_output_node(&mut self, path: &HardeenGraphPath, handle: &HardeenHandle) {
-        let graph = self.get_subgraph_from_path_mut(path);
+    pub fn remove_node(&mut self, handle: HardeenHandle) -> HardeenResult {
+        to_hardeen_result(self.project.remove_node(&to_node_handle(&handle)))
+    }
 
-        graph.set_output_node_handle(NodeHandle::new(handle.index, handle.generation));
+    pub fn connect_nodes_slotted(&mut self, from: &HardeenHandle, to: &HardeenHandle, slot: usize) -> HardeenResult {
+        to_hardeen_result(self.project.connect(&to_node_handle(from), &to_node_handle(to), slot))
     }
 
-    pub fn is_node_subgraph_processor(&self, path: &HardeenGraphPath, handle: &HardeenHandle) -> bool {
-        let graph = self.get_subgraph_from_path(path);
-        graph.is_node_subgraph_processor(&NodeHandle::new(handle.index, handle.generation)).unwrap()
+    /// Tells whether `from` could be connected to `slot` of `to`, e.g. while a link is dragged.
+    /// The slot is ignored for nodes with multiple inputs.
+    pub fn can_connect_nodes(&self, from: &HardeenHandle, to: &HardeenHandle, slot: usize) -> HardeenResult {
+        to_hardeen_result(self.project.get_current_graph()
+            .and_then(|graph| graph.can_connect(&to_node_handle(from), &to_node_handle(to), slot)))
     }
 
-    #[allow(non_snake_case)]
-    pub fn add_processor_node(&mut self, path: &HardeenGraphPath, typeName: &str) -> HardeenHandle {
-        let graph = self.get_subgraph_from_path_mut(path);
-        let handle = graph.add_processor_node_by_type(typeName);
+    pub fn connect_nodes(&mut self, from: &HardeenHandle, to: &HardeenHandle) -> HardeenResult {
+        to_hardeen_result(self.project.connect(&to_node_handle(from), &to_node_handle(to), 0))
+    }
+
+    pub fn disconnect_nodes_slotted(&mut self, from: &HardeenHandle, to: &HardeenHandle, slot: usize) -> HardeenResult {
+        to_hardeen_result(self.project.disconnect(&to_node_handle(from), &to_node_handle(to), slot))
+    }
+
+    pub fn disconnect_nodes(&mut self, from: &HardeenHandle, to: &HardeenHandle)  -> HardeenResult {
+        to_hardeen_result(self.project.disconnect(&to_node_handle(from), &to_node_handle(to), 0))
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.project.undo().unwrap_or(false)
+    }
 
-        HardeenHandle::new(handle.get_index(), handle.get_generation(), typeName)
+    pub fn redo(&mut self) -> bool {
+        self.project.redo().unwrap_or(false)
     }
 
-    pub fn remove_node(&mut self, path: &HardeenGraphPath, handle: HardeenHandle) -> HardeenResult {
-        let graph = self.get_subgraph_from_path_mut(path);
-        match graph.remove_node(NodeHandle::new(handle.index, handle.generation)) {
-                Ok(()) => HardeenResult::ok(),
-                Err(error) => HardeenResult::from(error)
+    pub fn get_node_name(&self, handle: &HardeenHandle) -> JsValue {
+        match self.project.get_current_graph().and_then(|graph| graph.get_node(&to_node_handle(handle))) {
+            Ok(node) => JsValue::from_str(node.get_name()),
+            Err(_) => JsValue::NULL
         }
     }
 
-    pub fn connect_nodes_slotted(&mut self, path: &HardeenGraphPath, from: &HardeenHandle, to: &HardeenHandle, slot: usize) -> HardeenResult {
-        let graph = self.get_subgraph_from_path_mut(path);
-        match graph.connect_to_slot(
-            &NodeHandle::new(from.index, from.generation), 
-            &NodeHandle::new(to.index, to.generation), slot) {
-                Ok(()) => HardeenResult::ok(),
-                Err(error) => HardeenResult::from(error)
+    pub fn set_node_name(&mut self, handle: &HardeenHandle, name: &str) -> HardeenResult {
+        to_hardeen_result(self.project.rename_node(&to_node_handle(handle), name))
+    }
+
+    /// Returns the editor metadata of a node as an object.
+    pub fn get_node_metadata(&self, handle: &HardeenHandle) -> JsValue {
+        match self.project.get_current_graph().and_then(|graph| graph.get_node(&to_node_handle(handle))) {
+            Ok(node) => JsValue::from_serde(node.get_metadata()).unwrap(),
+            Err(_) => JsValue::NULL
         }
     }
 
-    pub fn connect_nodes(&mut self, path: &HardeenGraphPath, from: &HardeenHandle, to: &HardeenHandle) -> HardeenResult {
-        let graph = self.get_subgraph_from_path_mut(path);
-        match graph.connect(
-            &NodeHandle::new(from.index, from.generation),
-            &NodeHandle::new(to.index, to.generation)) {
-                Ok(()) => HardeenResult::ok(),
-                Err(error) => HardeenResult::from(error)
+    /// Stores an editor metadata value like the position, color or comment of a node. Setting a key
+    /// to null or undefined removes it.
+    pub fn set_node_metadata(&mut self, handle: &HardeenHandle, key: &str, value: JsValue) -> HardeenResult {
+        let handle = to_node_handle(handle);
+
+        let value = if value.is_null() || value.is_undefined() {
+            None
+        } else {
+            match value.into_serde() {
+                Ok(value) => Some(value),
+                Err(_) => return HardeenResult::new("MetadataInvalid")
+            }
+        };
+
+        // The editor stores the positions of all nodes on every save, which would otherwise fill
+        // the history with edits that change nothing.
+        match self.project.get_current_graph().and_then(|graph| graph.get_node(&handle)) {
+            Ok(node) if node.get_metadata_value(key) == value.as_ref() => return HardeenResult::ok(),
+            Ok(_) => {}
+            Err(error) => return HardeenResult::from(error)
         }
+
+        to_hardeen_result(self.project.set_metadata_value(&handle, key, value))
     }
 
-    pub fn disconnect_nodes_slotted(&mut self, path: &HardeenGraphPath, from: &HardeenHandle, to: &HardeenHandle, slot: usize) -> HardeenResult {
-        let graph = self.get_subgraph_from_path_mut(path);
-        match graph.disconnect_from_slot(
-            &NodeHandle::new(from.index, from.generation), 
-            &NodeHandle::new(to.index, to.generation), slot) {
-                Ok(()) => HardeenResult::ok(),
-                Err(error) => HardeenResult::from(error)
+    /// Returns the whole project, including the editor metadata of its nodes and its definitions, as
+    /// a project file.
+    pub fn save_project(&self) -> JsValue {
+        match self.project.save_to_json() {
+            Ok(json) => JsValue::from_str(&json),
+            Err(_) => JsValue::NULL
         }
     }
 
-    pub fn disconnect_nodes(&mut self, path: &HardeenGraphPath, from: &HardeenHandle, to: &HardeenHandle)  -> HardeenResult {
-        let graph = self.get_subgraph_from_path_mut(path);
-        match graph.disconnect(
-            &NodeHandle::new(from.index, from.generation),
-            &NodeHandle::new(to.index, to.generation)) {
-                Ok(()) => HardeenResult::ok(),
-                Err(error) => HardeenResult::from(error)
+    pub fn load_project(&mut self, json: &str) -> HardeenResult {
+        match HardeenProject::load_from_json(json, ProcessorRegistry::with_builtin_processors()) {
+            Ok(project) => {
+                self.project = project;
+                self.lastResult = None;
+                self.lastError = None;
+                self.lastProfile = None;
+                HardeenResult::ok()
+            }
+            Err(error) => HardeenResult::from(error)
         }
     }
 
-    pub fn set_node_parameter(&mut self, path: &HardeenGraphPath, handle: &HardeenHandle, parameter: &str, value: &str) -> HardeenResult {
-        let h_handle = NodeHandle::new(handle.index, handle.generation);
-        let graph = self.get_subgraph_from_path_mut(path);
+    pub fn set_node_bypassed(&mut self, handle: &HardeenHandle, bypassed: bool) -> HardeenResult {
+        to_hardeen_result(self.project.set_bypassed(&to_node_handle(handle), bypassed))
+    }
+
+    pub fn is_node_bypassed(&self, handle: &HardeenHandle) -> bool {
+        self.project.get_current_graph()
+            .and_then(|graph| graph.get_node(&to_node_handle(handle)))
+            .is_ok_and(|node| node.is_bypassed())
+    }
 
-        if let Ok(node) = graph.get_node_mut(&h_handle) {
-            if let Err(error) = node.set_parameter(parameter, value) {
-                return HardeenResult::from(error);
-            }
-            graph.invalidate_cache(&h_handle);
+    pub fn set_node_muted(&mut self, handle: &HardeenHandle, muted: bool) -> HardeenResult {
+        to_hardeen_result(self.project.set_muted(&to_node_handle(handle), muted))
+    }
+
+    pub fn is_node_muted(&self, handle: &HardeenHandle) -> bool {
+        self.project.get_current_graph()
+            .and_then(|graph| graph.get_node(&to_node_handle(handle)))
+            .is_ok_and(|node| node.is_muted())
+    }
+
+    pub fn set_node_parameter(&mut self, handle: &HardeenHandle, parameter: &str, value: &str) -> HardeenResult {
+        let handle = to_node_handle(handle);
+
+        let value = self.project.get_current_graph()
+            .and_then(|graph| graph.get_node_parameter(&handle, parameter))
+            .and_then(|previous_value| Parameter::from_string(value, previous_value.get_type()));
+
+        match value {
+            Ok(value) => to_hardeen_result(self.project.set_parameter(&handle, parameter, value)),
+            Err(error) => HardeenResult::from(error)
         }
+    }
+
+    pub fn get_node_parameter(&self, handle: &HardeenHandle, parameter: &str) -> JsValue {
+        if let Ok(value) = self.project.get_current_graph().and_then(|graph| graph.get_node_parameter(&to_node_handle(handle), parameter)) {
+            return JsValue::from_str(&value.to_string());
+        }
+
+        JsValue::from_str("Error")
+    }
 
-        HardeenResult::ok()
+    /// Returns the parameters the subgraph of a node exposes, which are read and set like the
+    /// node's own parameters.
+    pub fn get_promoted_parameters(&self, handle: &HardeenHandle) -> JsValue {
+        match self.project.get_current_graph().and_then(|graph| graph.get_promoted_parameters(&to_node_handle(handle))) {
+            Ok(promoted_parameters) => JsValue::from_serde(&promoted_parameters).unwrap(),
+            Err(_) => JsValue::from_serde(&Vec::<PromotedParameter>::new()).unwrap()
+        }
     }
 
-     pub fn get_node_parameter(&mut self, path: &HardeenGraphPath, handle: &HardeenHandle, parameter: &str) -> JsValue {
-        let h_handle = NodeHandle::new(handle.index, handle.generation);
-        let graph = self.get_subgraph_from_path_mut(path);
-        
-        if let Ok(node) = graph.get_node(&h_handle)
-            {
-                if let Ok(value) = node.get_parameter(parameter) {
-                    return JsValue::from_str(&value.to_string());
-                }
+    /// Evaluates the current graph at the current frame of the project.
+    pub fn run_processors(&mut self) -> JsValue {
+        let profiling_enabled = self.profilingEnabled;
+        let context = self.project.get_evaluation_context();
+
+        let (result, profile) = match self.project.get_current_graph() {
+            Ok(graph) if profiling_enabled => {
+                let (result, profile) = graph.process_graph_output_with_profile(&context, true);
+                (result, Some(profile))
             }
+            Ok(graph) => (graph.process_graph_output_in_context(&context, true), None),
+            Err(error) => (Err(error), None)
+        };
 
-        JsValue::from_str("Error")
+        self.lastProfile = profile;
+
+        match result {
+            Ok(result) => {
+                self.lastResult = Some(result.clone());
+                self.lastError = None;
+                JsValue::from_serde(&(*result)).unwrap()
+            }
+            Err(error) => {
+                self.lastError = Some(error);
+                JsValue::from_str("No result")
+            }
+        }
+    }
+
+    pub fn get_frame(&self) -> f64 {
+        self.project.get_frame()
+    }
+
+    /// Sets the frame the following calls to `run_processors` evaluate.
+    pub fn set_frame(&mut self, frame: f64) {
+        self.project.set_frame(frame);
+    }
+
+    pub fn get_frame_rate(&self) -> f64 {
+        self.project.get_frame_rate()
+    }
+
+    #[allow(non_snake_case)]
+    pub fn set_frame_rate(&mut self, frameRate: f64) -> HardeenResult {
+        to_hardeen_result(self.project.set_frame_rate(frameRate))
     }
 
-    pub fn run_processors(&mut self, path: &HardeenGraphPath) -> JsValue {
-        let graph = self.get_subgraph_from_path_mut(path);
-        if let Ok(result) = graph.process_graph_output(true) {
-            self.lastResult = Some(result.clone());
-            return JsValue::from_serde(&(*result)).unwrap()
+    /// Returns the error of the last call to `run_processors` or null if it succeeded.
+    pub fn get_last_error(&self) -> JsValue {
+        match &self.lastError {
+            Some(error) => JsValue::from_serde(error).unwrap(),
+            None => JsValue::NULL
         }
+    }
 
-        JsValue::from_str("No result")
+    /// Returns the diagnostics of the root graph and all of its subgraphs.
+    pub fn validate_graph(&self) -> JsValue {
+        JsValue::from_serde(&self.project.get_root_graph().validate()).unwrap()
+    }
+
+    /// Enables or disables profiling for the following calls to `run_processors`.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profilingEnabled = enabled;
+    }
+
+    /// Returns the profile of the last call to `run_processors` or null if profiling was disabled.
+    pub fn get_last_profile(&self) -> JsValue {
+        match &self.lastProfile {
+            Some(profile) => JsValue::from_serde(profile).unwrap(),
+            None => JsValue::NULL
+        }
     }
 
     pub fn get_geometry_bounding_rect(&self) -> JsValue {
@@ -383,31 +572,22 @@ impl HardeenCoreInterface {
         JsValue::from_serde(&(Position(0.0, 0.0), Position( 0.0, 0.0))).unwrap()
     }
 
-    pub fn get_processor_parameters(&mut self, path: &HardeenGraphPath, handle: &HardeenHandle) -> JsValue {
-        let handle = NodeHandle::new(handle.index, handle.generation);
-        let graph = self.get_subgraph_from_path_mut(path);
-
-        if let Ok(node) = graph.get_node(&handle)
-        {
+    pub fn get_processor_parameters(&self, handle: &HardeenHandle) -> JsValue {
+        if let Ok(node) = self.project.get_current_graph().and_then(|graph| graph.get_node(&to_node_handle(handle))) {
             return JsValue::from_serde(node.get_parameters()).unwrap();
         }
 
         JsValue::from_str("No result")
     }
 
+    /// Returns the registered processor types followed by the definitions of the project.
     pub fn get_node_types(&self) -> JsValue {
-
-        JsValue::from_serde(&self.nodeTypes).unwrap()
+        JsValue::from_serde(&self.project.get_node_types()).unwrap()
     }
 
-    pub fn is_input_satisfied(&mut self, path: &HardeenGraphPath, handle: &HardeenHandle) -> bool {
-        let hardeen_handle = NodeHandle::new(handle.index, handle.generation);
-        let graph = self.get_subgraph_from_path_mut(path);
-
-        if let Ok(node) = graph.get_node(&hardeen_handle) {
-            return node.is_input_satisfied();
-        }
-
-        false
+    pub fn is_input_satisfied(&self, handle: &HardeenHandle) -> bool {
+        self.project.get_current_graph()
+            .and_then(|graph| graph.get_node(&to_node_handle(handle)))
+            .is_ok_and(|node| node.is_input_satisfied())
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,5 @@
 import {observable, computed, decorate} from "mobx";
-import { NodeType, HardeenCoreInterface, GeometryWorld, HardeenHandle, HardeenGraphPath } from '../../../hardeen_wasm/pkg';
+import { NodeType, HardeenCoreInterface, GeometryWorld, HardeenHandle } from '../../../hardeen_wasm/pkg';
 import Messenger from "./Messenger";
 import { HardeenNodeModel } from "../node-graph/nodes/HardeenNodeModel";
 
@@ -23,7 +23,7 @@ export class AppState {
     renderOutput : GeometryWorld;
     selectedNode : HardeenHandle;
     outputNode: HardeenNodeModel;
-    currentGraphPath: HardeenGraphPath;
+    currentGraphPath: string;
 
     inputFocused: boolean; // This is to prevent React-Diagram from taking over the input to our property editor fields (especially Backspace and Del)
 }
@@ -1,4 +1,4 @@
-import {HardeenHandle, NodeType, HardeenGraphPath} from "../../../hardeen_wasm/pkg";
+import {HardeenHandle, NodeType} from "../../../hardeen_wasm/pkg";
 import { HardeenNodeModel } from "../node-graph/nodes/HardeenNodeModel";
 
 export interface CreateNode {
@@ -64,13 +64,13 @@ export interface SwitchToSubgraph {
 
 export interface SwitchedToSubgraph {
     type: "SwitchedToSubgraph",
-    parentPath: HardeenGraphPath,
+    parentPath: string,
     displayName: string
 }
 
 export interface SwitchToGraphPath {
     type: "SwitchToGraphPath",
-    path: HardeenGraphPath
+    path: string
 }
 
 
@@ -3,7 +3,6 @@
 import { AppState } from "../app-state/AppState";
 import { observer } from "mobx-react";
 import * as React from "react";
-import { HardeenGraphPath } from "../../../hardeen_wasm/pkg/hardeen_wasm";
 import { SwitchToSubgraph, SwitchedToSubgraph } from "../app-state/Messenger";
 import {css, jsx} from "@emotion/core";
 
@@ -12,7 +11,7 @@ interface GraphLevelMenuProps {
 }
 
 interface GraphLevelMenuState {
-    graphPathStack: { path: HardeenGraphPath, displayName: string }[]
+    graphPathStack: { path: string, displayName: string }[]
 }
 
 const MenuStyle = css`
@@ -43,7 +42,7 @@ class GraphLevelMenu extends React.PureComponent<GraphLevelMenuProps, GraphLevel
         });
 
         this.state = {
-            graphPathStack: [ { path: props.appState.hardeenCore.get_root_path(), displayName: "Root" } ]
+            graphPathStack: [ { path: props.appState.hardeenCore.get_current_path(), displayName: "Root" } ]
         };
     }
 
@@ -3,13 +3,14 @@
 import * as React from "react";
 import {css, jsx} from "@emotion/core";
 import { AppState } from "../app-state/AppState";
-import { HardeenHandle } from '../../../hardeen_wasm/pkg';
+import { HardeenHandle, NodeTypeParameter } from '../../../hardeen_wasm/pkg';
 import { autorun } from "mobx";
 import { observer } from "mobx-react";
 
 interface AttributeProps {
     label: string,
     valueType: string,
+    parameter: NodeTypeParameter,
     value: string,
     onChange: Function,
     appState: AppState
@@ -48,19 +49,19 @@ class Attribute extends React.PureComponent<AttributeProps,AttributeState> {
         let inputComponent : JSX.Element = <div></div>;
 
         switch(this.props.valueType) {
-            case "bool": {
+            case "Boolean": {
                 inputComponent = this.renderBoolean();
                 break;
             }
-            case "f32": {
+            case "Float": {
                 inputComponent = this.renderF32();
                 break;
             }
-            case "u32": {
+            case "UnsignedInteger": {
                 inputComponent = this.renderU32();
                 break;
             }
-            case "i32": {
+            case "Integer": {
                 inputComponent = this.renderI32();
                 break;
             }
@@ -79,7 +80,7 @@ class Attribute extends React.PureComponent<AttributeProps,AttributeState> {
         }
 
         return <div>
-            <label>
+            <label title={this.props.parameter.description}>
                 {this.props.label}:
                 {
                     inputComponent
@@ -89,21 +90,33 @@ class Attribute extends React.PureComponent<AttributeProps,AttributeState> {
     }
 
     renderF32() {
-        return <input type="text" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
+        const p = this.props.parameter;
+        return <input type="number" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
+        min={p.min !== null ? p.min : undefined} max={p.max !== null ? p.max : undefined} step={p.step !== null ? p.step : "any"}
         onFocus={ (event) => { this.props.appState.inputFocused = true; }} onBlur={ () => { this.props.appState.inputFocused=false; } } />
     }
 
     renderU32() {
-        return <input type="text" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
+        const p = this.props.parameter;
+        return <input type="number" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
+        min={p.min !== null ? p.min : 0} max={p.max !== null ? p.max : undefined} step={p.step !== null ? p.step : 1}
         onFocus={ (event) => { this.props.appState.inputFocused = true; }} onBlur={ () => { this.props.appState.inputFocused=false; } } />
     }
 
     renderI32() {
-        return <input type="text" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
+        const p = this.props.parameter;
+        return <input type="number" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
+        min={p.min !== null ? p.min : undefined} max={p.max !== null ? p.max : undefined} step={p.step !== null ? p.step : 1}
         onFocus={ (event) => { this.props.appState.inputFocused = true; }} onBlur={ () => { this.props.appState.inputFocused=false; } } />
     }
 
     renderString() {
+        if(this.props.parameter.options.length > 0) {
+            return <select name={this.props.label} value={this.state.inputValue} onChange={this.handleOptionInput}>
+                { this.props.parameter.options.map( (option) => <option key={option} value={option}>{option}</option> ) }
+            </select>
+        }
+
         return <input type="text" name={this.props.label} value={this.state.inputValue} onChange={this.handleFloatInput}
         onFocus={ (event) => { this.props.appState.inputFocused = true; }} onBlur={ () => { this.props.appState.inputFocused=false; } } />
     }
@@ -160,6 +173,16 @@ class Attribute extends React.PureComponent<AttributeProps,AttributeState> {
         this.props.onChange(value);
     }
 
+    handleOptionInput = (event: React.FormEvent<HTMLSelectElement>) => {
+
+        const value = event.currentTarget.value;
+        this.setState({
+            inputValue: value
+        });
+
+        this.props.onChange(value);
+    }
+
     handleBooleanInput = (event: React.FormEvent<HTMLInputElement>) => {
 
         const value = event.currentTarget.checked ? "true" : "false";
@@ -237,14 +260,42 @@ class PropertyEditor extends React.Component<PropertyEditorProps, PropertyEditor
         const nodeHandle = this.state.selectedNode;
     
         const hc = this.props.appState.hardeenCore;
-        const graphPath = this.props.appState.currentGraphPath;
         
         return <div css={editorStyle}>
             <h2>{nodeType.name}</h2>
+            <div>
+                <label>Name:
+                    <input type="text" key={hc.get_node_name(nodeHandle)} defaultValue={hc.get_node_name(nodeHandle)}
+                    onFocus={ () => { this.props.appState.inputFocused = true; }}
+                    onBlur={ (event) => {
+                        this.props.appState.inputFocused = false;
+                        const result = hc.set_node_name(nodeHandle, event.currentTarget.value.trim());
+                        if(result.getResultType() !== "Ok") {
+                            event.currentTarget.value = hc.get_node_name(nodeHandle);
+                        }
+                    }} />
+                </label>
+            </div>
+            <div>
+                <label>Bypass:
+                    <input type="checkbox" checked={hc.is_node_bypassed(nodeHandle)} onChange={ (event) => {
+                        hc.set_node_bypassed(nodeHandle, event.currentTarget.checked);
+                        this.forceUpdate();
+                        this.props.appState.messenger.send({type: "RunProcessors"});
+                    }} />
+                </label>
+                <label>Mute:
+                    <input type="checkbox" checked={hc.is_node_muted(nodeHandle)} onChange={ (event) => {
+                        hc.set_node_muted(nodeHandle, event.currentTarget.checked);
+                        this.forceUpdate();
+                        this.props.appState.messenger.send({type: "RunProcessors"});
+                    }} />
+                </label>
+            </div>
             {
-                nodeType.parameters.map( (p) => {
-                    const value = hc.get_node_parameter(graphPath,nodeHandle, p.param_name);
-                    return <Attribute appState={this.props.appState} key={p.param_name} label={p.param_name} value={value} valueType={p.param_type} onChange={ (newValue: string) => {
+                hc.get_processor_parameters(nodeHandle).map( (p: NodeTypeParameter) => {
+                    const value = hc.get_node_parameter(nodeHandle, p.param_name);
+                    return <Attribute appState={this.props.appState} key={p.param_name} label={p.label} parameter={p} value={value} valueType={p.param_type} onChange={ (newValue: string) => {
                         this.setState( (oldState) => {
                             oldState.dirty[p.param_name] = newValue;
                             return {
@@ -254,16 +305,29 @@ class PropertyEditor extends React.Component<PropertyEditorProps, PropertyEditor
                     } }/>;
                 } )
             }
+            {
+                hc.get_promoted_parameters(nodeHandle).map( (p: any) => {
+                    const value = hc.get_node_parameter(nodeHandle, p.name);
+                    return <Attribute appState={this.props.appState} key={p.name} label={p.name} parameter={p.declaration} value={value} valueType={p.declaration.param_type} onChange={ (newValue: string) => {
+                        this.setState( (oldState) => {
+                            oldState.dirty[p.name] = newValue;
+                            return {
+                                dirty: oldState.dirty
+                            }
+                        });
+                    } }/>;
+                } )
+            }
             <button onClick={ () => {
                 Object.keys(this.state.dirty).forEach(paramName => {
-                    hc.set_node_parameter(graphPath,nodeHandle, paramName, this.state.dirty[paramName]);
+                    hc.set_node_parameter(nodeHandle, paramName, this.state.dirty[paramName]);
                     console.log(paramName+" => "+this.state.dirty[paramName]);
                 } );
                 this.setState({
                     dirty: {}
                 });
 
-                if(hc.is_input_satisfied(graphPath,nodeHandle)) {
+                if(hc.is_input_satisfied(nodeHandle)) {
                     this.props.appState.messenger.send({type: "RunProcessors"});
                 }
             }}>Update</button>
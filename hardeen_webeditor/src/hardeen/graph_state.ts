@@ -1,5 +1,4 @@
 import { HardeenNodeModel } from "../node-graph/nodes/HardeenNodeModel";
-import { HardeenGraphPath } from "../../../hardeen_wasm/pkg/hardeen_wasm";
 import { AppState } from "../app-state/AppState";
 import { SwitchToSubgraph, SwitchToGraphPath } from "../app-state/Messenger";
 import { DiagramModel, DiagramEngine } from "@projectstorm/react-diagrams";
@@ -12,20 +11,24 @@ interface GraphState {
 export const trackGraphStates = (model: DiagramModel, engine: DiagramEngine, appState: AppState) => {
     let graphStates : Map<string, GraphState> = new Map();
 
-    const switchToGraphPath = (graph_path: HardeenGraphPath) => {
+    const switchToGraphPath = (graph_path: string) => {
 
         const hc = appState.hardeenCore;
         let parentPath = appState.currentGraphPath;
 
+        if(hc.go_to_path(graph_path).getResultType() !== "Ok") {
+            return;
+        }
+
         const lastModel = {
 			outputNode: appState.outputNode,
 			model: model.serialize()
 		};
 
-		graphStates.set(hc.hash_graph_path(parentPath), lastModel);
+		graphStates.set(parentPath, lastModel);
 
-		appState.currentGraphPath = graph_path;
-		let subgraphState = graphStates.get(hc.hash_graph_path(graph_path));
+		appState.currentGraphPath = hc.get_current_path();
+		let subgraphState = graphStates.get(appState.currentGraphPath);
 
 		if(subgraphState!=undefined) {
 			model.deserializeModel(subgraphState.model as any, engine);
@@ -37,7 +40,7 @@ export const trackGraphStates = (model: DiagramModel, engine: DiagramEngine, app
 			}
 			
 			model.deserializeModel((subgraphState.model as DiagramModel).serialize(), engine);
-			graphStates.set(hc.hash_graph_path(graph_path), subgraphState);
+			graphStates.set(appState.currentGraphPath, subgraphState);
         }
 
 		appState.selectedNode = null;
@@ -53,15 +56,12 @@ export const trackGraphStates = (model: DiagramModel, engine: DiagramEngine, app
     appState.messenger.subscribe("SwitchToSubgraph", (message: SwitchToSubgraph) => {
 
         const hc = appState.hardeenCore;
-        let parentPath = appState.currentGraphPath;
-
-        const subgraphPath = hc.get_graph_path(parentPath, message.node.getHardeenHandle())
 
-        switchToGraphPath(subgraphPath);
+        switchToGraphPath(hc.get_node_name(message.node.getHardeenHandle()));
 
         appState.messenger.send({
             type: "SwitchedToSubgraph",
-            parentPath: subgraphPath,
+            parentPath: appState.currentGraphPath,
             displayName: message.node.getHardeenHandle().get_node_type()
         });
     });
@@ -1,5 +1,4 @@
 import { AppState } from "../app-state/AppState";
-import { HardeenGraphPath } from '../../../hardeen_wasm/pkg/hardeen_wasm';
 import {CreateNode, SaveAll, CreateLink, DeleteLink, SetOutputNode, NodeSelected, DeleteNode, SubgraphNodeSelected, RunProcessors} from "../app-state/Messenger";
 import { HardeenNodeModel } from '../node-graph/nodes/HardeenNodeModel';
 import { DiagramEngine, DiagramModel } from "@projectstorm/react-diagrams";
@@ -7,11 +6,12 @@ import { DiagramEngine, DiagramModel } from "@projectstorm/react-diagrams";
 export const registerMessageHandler = (model: DiagramModel, engine: DiagramEngine, appState: AppState) => {
     
 	appState.messenger.subscribe("CreateNode", (message: CreateNode) => {
-		const handle = appState.hardeenCore.add_processor_node(appState.currentGraphPath,message.nodeType.name);
-		const isSubgraphProcessor = appState.hardeenCore.is_node_subgraph_processor(appState.currentGraphPath, handle);
+		const handle = appState.hardeenCore.add_processor_node(message.nodeType.name);
+		const isSubgraphProcessor = appState.hardeenCore.is_node_subgraph_processor(handle);
 
 		const node = new HardeenNodeModel({hardeenHandle: handle, nodeType: message.nodeType, isSubgraphProcessor: isSubgraphProcessor});
 		node.setPosition(50,50);
+		appState.hardeenCore.set_node_metadata(handle, "position", [50, 50]);
 		model.addNode(node);
 		engine.repaintCanvas();
 		appState.messenger.send({type: "NodeCreated", hdNodeHandle: handle});
@@ -22,14 +22,14 @@ export const registerMessageHandler = (model: DiagramModel, engine: DiagramEngin
 			appState.selectedNode = null;
 		}
 
-		appState.hardeenCore.remove_node(appState.currentGraphPath,message.hdNodeHandle);
+		appState.hardeenCore.remove_node(message.hdNodeHandle);
 	});
 
 	appState.messenger.subscribe("CreateLink", (message: CreateLink) => {
 		if(message.slot != undefined) {
-			appState.hardeenCore.connect_nodes_slotted(appState.currentGraphPath,message.from, message.to, message.slot);
+			appState.hardeenCore.connect_nodes_slotted(message.from, message.to, message.slot);
 		} else {
-			appState.hardeenCore.connect_nodes(appState.currentGraphPath,message.from, message.to);
+			appState.hardeenCore.connect_nodes(message.from, message.to);
 		}
 	});
 
@@ -41,15 +41,22 @@ export const registerMessageHandler = (model: DiagramModel, engine: DiagramEngin
 
 		if(message.hasOwnProperty("slot")) {
 
-			appState.hardeenCore.disconnect_nodes_slotted(appState.currentGraphPath,message.from, message.to, message.slot);
+			appState.hardeenCore.disconnect_nodes_slotted(message.from, message.to, message.slot);
 		} else {
-			appState.hardeenCore.disconnect_nodes(appState.currentGraphPath,message.from, message.to);
+			appState.hardeenCore.disconnect_nodes(message.from, message.to);
 		}
 	});
 
 	let savedModel = null;
 
 	appState.messenger.subscribe("SaveAll", (message: SaveAll) => {
+		model.getNodes().forEach((node) => {
+			if(node instanceof HardeenNodeModel) {
+				const position = node.getPosition();
+				appState.hardeenCore.set_node_metadata(node.getHardeenHandle(), "position", [position.x, position.y]);
+			}
+		});
+
 		const serializedModel = model.serialize();
 
 		if(savedModel!=null) {
@@ -76,11 +83,14 @@ export const registerMessageHandler = (model: DiagramModel, engine: DiagramEngin
     
     appState.messenger.subscribe("RunProcessors", (message: RunProcessors) => {
  
-        appState.hardeenCore.set_output_node(appState.currentGraphPath,appState.outputNode.getHardeenHandle());
-        const result = appState.hardeenCore.run_processors(appState.currentGraphPath);
+        appState.hardeenCore.set_output_node(appState.outputNode.getHardeenHandle());
+        const result = appState.hardeenCore.run_processors();
 
         if(result!="No result") {
-            appState.renderOutput = appState.hardeenCore.run_processors(appState.currentGraphPath);
+            appState.renderOutput = appState.hardeenCore.run_processors();
+        }
+        else {
+            console.error(appState.hardeenCore.get_last_error());
         }
 	});
 
@@ -98,7 +108,7 @@ export const registerMessageHandler = (model: DiagramModel, engine: DiagramEngin
 	appState.messenger.subscribe("MoveLevelUp", (message) => {
         appState.messenger.send({
             type: "SwitchToGraphPath",
-            path: appState.hardeenCore.get_root_path()
+            path: "/root"
         })
 	});
 }
\ No newline at end of file
@@ -55,9 +55,9 @@ import(/* webpackChunkName: "hardeen" */ "../../hardeen_wasm/pkg" ).then( (harde
 
 	const model = new DiagramModel();
 
-	appState.currentGraphPath = appState.hardeenCore.get_root_path();
+	appState.currentGraphPath = appState.hardeenCore.get_current_path();
 
-	const handle = appState.hardeenCore.add_processor_node(appState.currentGraphPath,"Empty");
+	const handle = appState.hardeenCore.add_processor_node("Empty");
 	const node = new HardeenNodeModel({hardeenHandle: handle, nodeType: appState.allNodeTypes[0], isSubgraphProcessor: false});
 	node.setPosition(50,50);
 	model.addNode(node);